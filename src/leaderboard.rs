@@ -0,0 +1,94 @@
+// the leaderboard: scores of won games, kept in a local file with one line per score
+// (its points, the board it was won on and the player's name, separated by tabs).
+// scores are only compared with others won on the same board settings
+
+use std::fmt;
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::str::FromStr;
+
+// number of top scores displayed
+pub const LEADERBOARD_SIZE: usize = 10;
+
+// struct to store a score on the leaderboard
+#[derive(Clone, Debug, PartialEq)]
+pub struct Score {
+    pub points: usize, // points scored
+    pub board: String, // board settings it was won on, eg. "9x9 normal 10"
+    pub name: String,  // name of the player
+}
+
+// err to raise if parse from str fails
+#[derive(Debug, PartialEq)]
+pub struct ScoreParseError;
+
+impl fmt::Display for ScoreParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Invalid score")
+    }
+}
+
+// impl ability to parse from str
+impl FromStr for Score {
+    // err to return if parsing fails
+    type Err = ScoreParseError;
+
+    // fn to parse score from a line of the leaderboard file
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut fields = s.splitn(3, '\t');
+        let points = fields.next().and_then(|x| x.parse::<usize>().ok());
+        match (points, fields.next(), fields.next()) {
+            (Some(points), Some(board), Some(name)) => Ok(Self {
+                points,
+                board: board.to_owned(),
+                name: name.to_owned(),
+            }),
+            _ => Err(ScoreParseError),
+        }
+    }
+}
+
+// impl display, as a line of the leaderboard file
+impl fmt::Display for Score {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}\t{}\t{}", self.points, self.board, self.name)
+    }
+}
+
+// fn to read every score from a leaderboard file, skipping malformed lines
+// (a missing file is an empty leaderboard)
+pub fn load(path: &str) -> io::Result<Vec<Score>> {
+    match std::fs::read_to_string(path) {
+        Ok(file) => Ok(file.lines().filter_map(|line| line.parse().ok()).collect()),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(err) => Err(err),
+    }
+}
+
+// fn to add a score to the end of a leaderboard file, creating it if needed
+pub fn record(path: &str, score: &Score) -> io::Result<()> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{score}")
+}
+
+// fn to get the top scores won on a board, highest first
+// (ties keep the order they were recorded in, so the first to get a score stays ahead)
+pub fn top<'a>(scores: &'a [Score], board: &str) -> Vec<&'a Score> {
+    let mut top: Vec<&Score> = scores.iter().filter(|score| score.board == board).collect();
+    top.sort_by_key(|score| std::cmp::Reverse(score.points));
+    top.truncate(LEADERBOARD_SIZE);
+    top
+}
+
+// fn to format the top scores won on a board, for display
+pub fn format_top(scores: &[Score], board: &str) -> String {
+    let top = top(scores, board);
+    if top.is_empty() {
+        return format!("No scores yet for {board}");
+    }
+    let mut leaderboard = format!("Leaderboard for {board}:");
+    for (i, score) in top.iter().enumerate() {
+        leaderboard += &format!("\n{:>2}. {:>6}  {}", i + 1, score.points, score.name);
+    }
+    leaderboard
+}
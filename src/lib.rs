@@ -0,0 +1,4771 @@
+// the game: its core (the board, its rules and the solver) makes moves and describes the board
+// without reading input or printing anything, so it can be played from elsewhere, and run()
+// plays it in the terminal from the command line args
+
+use leaderboard::Score;
+pub use multi::MultiBoard;
+#[cfg(feature = "rand")]
+use rand::prelude::*;
+use rng::random_seed;
+pub use rng::SeededRng;
+pub use solver::{DeductionStep, FrontierPartition, GuessStrategy, Technique};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::io::{self, stdin, stdout, IsTerminal, Write};
+use std::ops::Range;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, PoisonError, RwLock, RwLockReadGuard};
+use std::thread;
+use std::time::{Duration, Instant};
+use terminal::terminal_size;
+pub use theme::{Palette, Theme};
+
+mod leaderboard;
+mod multi;
+mod rng;
+mod solver;
+mod terminal;
+#[cfg(test)]
+mod tests;
+mod theme;
+
+// type alias for coordinates in the minesweeper grid
+pub type Position = (usize, usize);
+
+// type alias for a move made by the player: its type and position
+pub type Move = (MoveType, Position);
+
+// type alias for a custom win condition, for modes with another goal than opening every safe
+// square: the game is won as soon as it returns true (shared between clones of the game)
+pub type WinCondition = Arc<dyn Fn(&Minesweeper) -> bool + Send + Sync>;
+
+// fn to get the chebyshev distance between two positions
+// (the number of king moves needed to get from one to the other)
+fn chebyshev_distance(a: Position, b: Position) -> usize {
+    a.0.abs_diff(b.0).max(a.1.abs_diff(b.1))
+}
+
+// fn to get the spreadsheet-style letters naming a col: A-Z, then AA-AZ, BA-BZ and so on
+// (bijective base 26, so there is no zero digit)
+fn column_name(x: usize) -> String {
+    let mut name = String::new();
+    let mut remaining = x + 1;
+    while remaining > 0 {
+        remaining -= 1;
+        name.insert(0, char::from(b'A' + (remaining % 26) as u8));
+        remaining /= 26;
+    }
+    name
+}
+
+// fn to parse spreadsheet-style letters naming a col (in any case) back into its index
+// returns none if there are no letters, anything else, or the col is too big for a usize
+fn parse_column_name(raw: &str) -> Option<usize> {
+    // guard to check if name is empty
+    if raw.is_empty() {
+        return None;
+    }
+    raw.chars()
+        .try_fold(0usize, |number, c| {
+            let digit = c
+                .is_ascii_alphabetic()
+                .then(|| c.to_ascii_uppercase() as usize)?;
+            number
+                .checked_mul(26)?
+                .checked_add(digit - 'A' as usize + 1)
+        })
+        .map(|number| number - 1)
+}
+
+// fn to format labels for each of a row of cols, eg. col mine counts or names
+// labels longer than one char are written vertically, right-aligned one char per line,
+// so each stays lined up with the squares of its col
+fn format_col_labels(labels: &[String]) -> String {
+    let length = labels.iter().map(String::len).max().unwrap_or(0);
+    let labels: Vec<Vec<char>> = labels
+        .iter()
+        .map(|label| format!("{label:>length$}").chars().collect())
+        .collect();
+
+    let mut formatted = String::new();
+    for i in 0..length {
+        formatted += "  ";
+        for label in &labels {
+            formatted.push(label[i]);
+            formatted.push(' ');
+        }
+        formatted = formatted.trim_end().to_owned() + "\n";
+    }
+    formatted
+}
+
+// fn to get the name of a move recorded for replays (an open, flag or surrender)
+fn recorded_move_name(move_type: MoveType) -> &'static str {
+    match move_type {
+        MoveType::Open => "open",
+        MoveType::Surrender => "surrender",
+        _ => "flag",
+    }
+}
+
+// struct to store prompts to display instead of the game's own move prompts
+// (each on the same line as the input, and any not set are displayed as usual)
+#[derive(Clone, Default)]
+pub struct Prompts {
+    pub x: Option<String>, // prompt for the move x (or a square, or x y pairs)
+    pub y: Option<String>, // prompt for the move y
+    pub move_type: Option<String>, // prompt for the move type
+}
+
+// fn to display a prompt for input: the custom prompt if there is one (on the same line as the
+// input), otherwise the message unless quiet
+fn write_prompt(
+    out: &mut impl Write,
+    msg: &str,
+    prompt: Option<&str>,
+    quiet: bool,
+) -> io::Result<()> {
+    match prompt {
+        Some(prompt) => {
+            write!(out, "{prompt}")?;
+            out.flush()
+        }
+        None if quiet => Ok(()),
+        None => writeln!(out, "{msg}"),
+    }
+}
+
+// fn to get input from user, prompting with the message (or the custom prompt, if given)
+fn get_input(msg: &str, prompt: Option<&str>, quiet: bool) -> String {
+    // create empty buffer to read input into
+    let mut input_string = String::new();
+    // read input until it contains something
+    while input_string.trim().is_empty() {
+        // ignore err as the prompt is only a convenience
+        let _ = write_prompt(&mut stdout(), msg, prompt, quiet);
+        // read input into buffer and if err, clear and try again
+        if stdin().read_line(&mut input_string).is_err() {
+            input_string.clear();
+            println!("Error reading input.");
+        }
+    }
+    // return valid input
+    input_string
+}
+
+// enum to store game state
+#[derive(Clone, Copy, PartialEq)]
+pub enum GameState {
+    Playing,
+    Won,
+    Lost,
+    Quit, // the player stopped before the game was won or lost
+}
+
+impl GameState {
+    // fn to get the name of a game state (as used in saves)
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Playing => "playing",
+            Self::Won => "won",
+            Self::Lost => "lost",
+            // a quit game can still be resumed, so is saved as it was
+            Self::Quit => "playing",
+        }
+    }
+}
+
+// impl ability to parse from str (used for loading saves)
+impl FromStr for GameState {
+    // err to return if parsing fails
+    type Err = LoadError;
+
+    // fn to parse game state from str
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "playing" => Ok(Self::Playing),
+            "won" => Ok(Self::Won),
+            "lost" => Ok(Self::Lost),
+            _ => Err(LoadError::Malformed),
+        }
+    }
+}
+
+// enum to store type of move made by user
+#[derive(Clone, Copy, PartialEq)]
+pub enum MoveType {
+    Flag,
+    Open,
+    ToggleAdjacency,
+    ToggleStatus,
+    Resize,
+    Clues,
+    Satisfied,
+    Surrender,
+    Explain,
+    Weights,
+    Show(usize),
+    ChangeVariant(MinesweeperVariant),
+    Nearest,
+    MinesNear,
+    Frontier,
+    Hash,
+    Difficulty,
+    Distance,
+    Help,
+    Pause,
+    Minimap,
+    Repeat,
+    Quit,
+}
+
+// err to raise if move validation fails (out of bounds, etc)
+#[derive(Debug, PartialEq)]
+pub struct MoveValidationError;
+
+impl fmt::Display for MoveValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Invalid move")
+    }
+}
+
+// enum to store an action that steps the game: the moves which change the board, as one
+// interface for every way of playing it (the cli, replays, the solver, multiple boards)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Action {
+    Open(Position),      // open a square
+    Flag(Position),      // flag a square, or unflag it if flagged
+    Surrender(Position), // give up the game, on the square entered
+    Quit,                // stop playing, leaving the game as it is (to be resumed)
+}
+
+impl Action {
+    // fn to get the action for a move recorded for replays, if it is one
+    fn from_move((move_type, pos): Move) -> Option<Self> {
+        match move_type {
+            MoveType::Open => Some(Self::Open(pos)),
+            MoveType::Flag => Some(Self::Flag(pos)),
+            MoveType::Surrender => Some(Self::Surrender(pos)),
+            MoveType::Quit => Some(Self::Quit),
+            _ => None,
+        }
+    }
+}
+
+// impl display, in the same form as moves in replays (0-based), eg. "open 3,4"
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Open((x, y)) => write!(f, "open {x},{y}"),
+            Self::Flag((x, y)) => write!(f, "flag {x},{y}"),
+            Self::Surrender((x, y)) => write!(f, "surrender {x},{y}"),
+            Self::Quit => write!(f, "quit"),
+        }
+    }
+}
+
+// impl ability to parse from str
+impl FromStr for Action {
+    // err to return if parsing fails
+    type Err = MoveValidationError;
+
+    // fn to parse action from str, as displayed
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // guard to check for the one action without a square
+        if s.trim() == "quit" {
+            return Ok(Self::Quit);
+        }
+        let (name, raw_pos) = s.trim().split_once(' ').ok_or(MoveValidationError)?;
+        let (raw_x, raw_y) = raw_pos.split_once(',').ok_or(MoveValidationError)?;
+        let pos = match (raw_x.parse::<usize>(), raw_y.parse::<usize>()) {
+            (Ok(x), Ok(y)) => (x, y),
+            _ => return Err(MoveValidationError),
+        };
+        match name {
+            "open" => Ok(Self::Open(pos)),
+            "flag" => Ok(Self::Flag(pos)),
+            "surrender" => Ok(Self::Surrender(pos)),
+            _ => Err(MoveValidationError),
+        }
+    }
+}
+
+// enum to store what an action did
+// (whether it won or lost the game is left to the game's state)
+#[derive(Debug, PartialEq)]
+pub enum ActionResult {
+    Opened(usize), // this many squares were opened (the square and any cascade from it)
+    MineHit,       // a mine was opened, losing a life (or the game, if there were none left)
+    Flagged,       // the square was flagged
+    Unflagged,     // the square's flag was removed
+    Ended,         // the game was given up or quit
+    Nothing,       // nothing changed (eg. the square was open, or the game is over)
+}
+
+// err to raise if board size validation fails (not a number, zero, too big, etc)
+#[derive(Debug, PartialEq)]
+struct SizeValidationError;
+
+impl fmt::Display for SizeValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Invalid size")
+    }
+}
+
+// err to raise if a board is too big to write as a .mbf file
+#[derive(Debug, PartialEq)]
+pub struct MbfSizeError;
+
+impl fmt::Display for MbfSizeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Board too big for a .mbf file: at most {MBF_MAX_SIZE}x{MBF_MAX_SIZE} fits"
+        )
+    }
+}
+
+// err to raise if a square to open or flag is off the board (or outside its shape)
+#[derive(Debug, PartialEq)]
+pub struct OutOfBounds;
+
+impl fmt::Display for OutOfBounds {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Square out of bounds")
+    }
+}
+
+// err to raise if loading a saved game fails
+#[derive(Debug, PartialEq)]
+pub enum LoadError {
+    Malformed,                   // missing or malformed fields, etc
+    Inconsistent(Vec<Position>), // squares whose saved state could never occur in a real game
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Malformed => write!(f, "Invalid save"),
+            // list offending squares as 1-based move coords
+            Self::Inconsistent(squares) => write!(
+                f,
+                "Inconsistent save at {}",
+                squares
+                    .iter()
+                    .map(|(x, y)| format!("({}, {})", x + 1, y + 1))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+        }
+    }
+}
+
+// enum to store all minesweeper variants
+#[derive(Clone, Copy, PartialEq)]
+pub enum MinesweeperVariant {
+    Normal,        // all mines in 3x3 area around square
+    FarNormal,     // all mines in 5x5 area around square
+    KnightPaths,   // all mines in knight paths from square
+    BlindUp,       // all mines in 3x3 area around square excl square directly above
+    BlindDown,     // all mines in 3x3 area around square excl square directly below
+    BlindLeft,     // all mines in 3x3 area around square excl square directly left
+    BlindRight,    // all mines in 3x3 area around square excl square directly right
+    Orthogonal,    // all mines orthogonally adjacent to square (distance 1)
+    FarOrthogonal, // all mines orthogonally adjacent to square (distance 2)
+    Diagonal,      // all mines diagonally adjacent to square (distance 1)
+    FarDiagonal,   // all mines diagonally adjacent to square (distance 2)
+    Doubled,       // all mines in 3x3 area around square but orthogonally adj squares counted twice
+    LTromino,      // all mines in L-tromino cornered at square (squares directly above and right)
+    Plus,          // all mines in plus shape around square (same as orthogonal)
+    Cross,         // all mines in X shape around square (same as diagonal)
+    Ring,          // all mines on border of 5x5 area around square excl inner 3x3 area
+    Custom(usize), // a variant registered at runtime (index into the registry)
+}
+
+impl MinesweeperVariant {
+    // list of all variants, in the order they are displayed
+    const ALL: [Self; 16] = [
+        Self::Normal,
+        Self::FarNormal,
+        Self::KnightPaths,
+        Self::BlindUp,
+        Self::BlindDown,
+        Self::BlindLeft,
+        Self::BlindRight,
+        Self::Orthogonal,
+        Self::FarOrthogonal,
+        Self::Diagonal,
+        Self::FarDiagonal,
+        Self::Doubled,
+        Self::LTromino,
+        Self::Plus,
+        Self::Cross,
+        Self::Ring,
+    ];
+
+    // fn to get the name of a variant (as used on the command line)
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Normal => "normal",
+            Self::FarNormal => "far-normal",
+            Self::KnightPaths => "knight-paths",
+            Self::BlindUp => "blind-up",
+            Self::BlindDown => "blind-down",
+            Self::BlindLeft => "blind-left",
+            Self::BlindRight => "blind-right",
+            Self::Orthogonal => "orthogonal",
+            Self::FarOrthogonal => "far-orthogonal",
+            Self::Diagonal => "diagonal",
+            Self::FarDiagonal => "far-diagonal",
+            Self::Doubled => "doubled",
+            Self::LTromino => "l-tromino",
+            Self::Plus => "plus",
+            Self::Cross => "x",
+            Self::Ring => "ring",
+            Self::Custom(index) => registry()[*index].name,
+        }
+    }
+
+    // fn to get a description of which squares a variant counts mines in
+    fn description(&self) -> &'static str {
+        match self {
+            Self::Normal => "all mines in 3x3 area around square",
+            Self::FarNormal => "all mines in 5x5 area around square",
+            Self::KnightPaths => "all mines in knight paths from square",
+            Self::BlindUp => "all mines in 3x3 area around square excl square directly above",
+            Self::BlindDown => "all mines in 3x3 area around square excl square directly below",
+            Self::BlindLeft => "all mines in 3x3 area around square excl square directly left",
+            Self::BlindRight => "all mines in 3x3 area around square excl square directly right",
+            Self::Orthogonal => "all mines orthogonally adjacent to square (distance 1)",
+            Self::FarOrthogonal => "all mines orthogonally adjacent to square (distance 2)",
+            Self::Diagonal => "all mines diagonally adjacent to square (distance 1)",
+            Self::FarDiagonal => "all mines diagonally adjacent to square (distance 2)",
+            Self::Doubled => {
+                "all mines in 3x3 area around square but orthogonally adj squares counted twice"
+            }
+            Self::LTromino => {
+                "all mines in L-tromino cornered at square (squares directly above and right)"
+            }
+            Self::Plus => "all mines in plus shape around square (same as orthogonal)",
+            Self::Cross => "all mines in X shape around square (same as diagonal)",
+            Self::Ring => "all mines on border of 5x5 area around square excl inner 3x3 area",
+            Self::Custom(index) => registry()[*index].description,
+        }
+    }
+
+    // fn to get all variants: the built-in ones, then those registered at runtime
+    fn all() -> Vec<Self> {
+        let num_custom = registry().len();
+        Self::ALL
+            .into_iter()
+            .chain((0..num_custom).map(Self::Custom))
+            .collect()
+    }
+
+    // fn to generate a list of all variants and their descriptions, one per line
+    fn list() -> String {
+        Self::all()
+            .iter()
+            .map(|variant| format!("\n\t{}: {}", variant.name(), variant.description()))
+            .collect()
+    }
+
+    // fn to get the neighbor offsets of a variant (the squares it counts mines in, relative
+    // to the square the number is on)
+    fn offsets(&self) -> Vec<(isize, isize)> {
+        use MinesweeperVariant::{
+            BlindDown, BlindLeft, BlindRight, BlindUp, Cross, Diagonal, Doubled, FarDiagonal,
+            FarNormal, FarOrthogonal, KnightPaths, LTromino, Normal, Orthogonal, Plus, Ring,
+        };
+        match self {
+            // all mines in 3x3 area around square
+            Normal => vec![
+                (-1, 0),
+                (1, 0),
+                (0, -1),
+                (0, 1),
+                (-1, -1),
+                (-1, 1),
+                (1, -1),
+                (1, 1),
+            ],
+            // all mines in 5x5 area around square
+            FarNormal => (-2..=2)
+                .flat_map(|x| (-2..=2).map(move |y| (x, y)))
+                .collect(),
+            // all mines in knight paths from square
+            KnightPaths => vec![
+                (-1, -2),
+                (-1, 2),
+                (1, -2),
+                (1, 2),
+                (-2, -1),
+                (-2, 1),
+                (2, -1),
+                (2, 1),
+            ],
+            // all mines in 3x3 area around square excl square directly above
+            BlindUp => vec![(-1, 0), (1, 0), (0, 1), (-1, -1), (-1, 1), (1, -1), (1, 1)],
+            // all mines in 3x3 area around square excl square directly below
+            BlindDown => vec![(-1, 0), (1, 0), (0, -1), (-1, -1), (-1, 1), (1, -1), (1, 1)],
+            // all mines in 3x3 area around square excl square directly left
+            BlindLeft => vec![(1, 0), (0, -1), (0, 1), (-1, -1), (-1, 1), (1, -1), (1, 1)],
+            // all mines in 3x3 area around square excl square directly right
+            BlindRight => vec![(-1, 0), (0, -1), (0, 1), (-1, -1), (-1, 1), (1, -1), (1, 1)],
+            // all mines orthogonally adjacent to square (distance 1)
+            Orthogonal => vec![(-1, 0), (1, 0), (0, -1), (0, 1)],
+            // all mines orthogonally adjacent to square (distance 2)
+            FarOrthogonal => vec![
+                (-2, 0),
+                (2, 0),
+                (0, -2),
+                (0, 2),
+                (-1, 0),
+                (1, 0),
+                (0, -1),
+                (0, 1),
+            ],
+            // all mines diagonally adjacent to square (distance 1)
+            Diagonal => vec![(-1, -1), (1, 1), (-1, 1), (1, -1)],
+            // all mines diagonally adjacent to square (distance 2)
+            FarDiagonal => vec![
+                (-2, -2),
+                (2, 2),
+                (-2, 2),
+                (2, -2),
+                (-1, -1),
+                (1, 1),
+                (-1, 1),
+                (1, -1),
+            ],
+            // all mines in 3x3 area around square but orthogonally adj squares counted twice
+            Doubled => vec![
+                (-1, 0),
+                (1, 0),
+                (0, -1),
+                (0, 1),
+                (-1, 0),
+                (1, 0),
+                (0, -1),
+                (0, 1),
+                (-1, -1),
+                (-1, 1),
+                (1, -1),
+                (1, 1),
+            ],
+            // all mines in L-tromino cornered at square (squares directly above and right)
+            LTromino => vec![(0, -1), (1, 0)],
+            // all mines in plus shape around square (same as orthogonal)
+            Plus => vec![(-1, 0), (1, 0), (0, -1), (0, 1)],
+            // all mines in X shape around square (same as diagonal)
+            Cross => vec![(-1, -1), (1, 1), (-1, 1), (1, -1)],
+            // all mines on border of 5x5 area around square excl inner 3x3 area
+            Ring => (-2..=2)
+                .flat_map(|x| (-2..=2).map(move |y| (x, y)))
+                .filter(|&(x, y): &(isize, isize)| x.abs() == 2 || y.abs() == 2)
+                .collect(),
+            // generated by the variant's registered offset generator
+            Self::Custom(index) => (registry()[*index].offsets)(),
+        }
+    }
+
+    // fn to register a new variant at runtime, without adding it to the enum
+    // its offsets are generated by the given fn each time they are needed, and it can be
+    // parsed from its name like the built-in variants. errs if the name is already taken.
+    pub fn register(
+        name: &'static str,
+        description: &'static str,
+        offsets: impl Fn() -> Vec<(isize, isize)> + Send + Sync + 'static,
+    ) -> Result<Self, VariantRegistrationError> {
+        // hold the write lock throughout, so two registrations can't both take the same name
+        // (names are checked without parsing, which would need the lock again)
+        let mut registry = CUSTOM_VARIANTS
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+        let lowercase = name.to_lowercase();
+        // guard to check if a variant (or alias) already has this name
+        if lowercase == "cross"
+            || Self::ALL.iter().any(|variant| variant.name() == lowercase)
+            || registry.iter().any(|variant| variant.name == lowercase)
+        {
+            return Err(VariantRegistrationError);
+        }
+        registry.push(CustomVariant {
+            name,
+            description,
+            offsets: Box::new(offsets),
+        });
+        Ok(Self::Custom(registry.len() - 1))
+    }
+}
+
+// type alias for a fn generating the neighbor offsets of a custom variant
+type OffsetGenerator = Box<dyn Fn() -> Vec<(isize, isize)> + Send + Sync>;
+
+// struct to store a variant registered at runtime
+struct CustomVariant {
+    name: &'static str,        // name of variant (as used on the command line)
+    description: &'static str, // description of which squares it counts mines in
+    offsets: OffsetGenerator,  // fn generating its neighbor offsets
+}
+
+// variants registered at runtime, indexed by MinesweeperVariant::Custom
+// (variants are never unregistered, so indexes stay valid)
+static CUSTOM_VARIANTS: RwLock<Vec<CustomVariant>> = RwLock::new(Vec::new());
+
+// fn to read the registered variants
+fn registry() -> RwLockReadGuard<'static, Vec<CustomVariant>> {
+    CUSTOM_VARIANTS
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+}
+
+// err to raise if registering a variant fails (its name is already taken)
+#[derive(Debug, PartialEq)]
+pub struct VariantRegistrationError;
+
+impl fmt::Display for VariantRegistrationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Variant name already taken")
+    }
+}
+
+// err to raise if parse from str fails
+#[derive(Debug, PartialEq)]
+pub struct VariantParseError;
+
+impl fmt::Display for VariantParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Invalid variant")
+    }
+}
+
+// impl ability to parse from str
+impl FromStr for MinesweeperVariant {
+    // err to return if parsing fails
+    type Err = VariantParseError;
+
+    // fn to parse variant from str
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            // alias for x
+            "cross" => Ok(Self::Cross),
+            // find variant with matching name (including those registered at runtime)
+            name => Self::all()
+                .into_iter()
+                .find(|variant| variant.name() == name)
+                .ok_or(VariantParseError),
+        }
+    }
+}
+
+// enum to store the ways the whole board can be rotated or reflected
+#[derive(Clone, Copy, PartialEq)]
+pub enum Transform {
+    Rotate,         // a quarter turn clockwise
+    FlipHorizontal, // mirrored top to bottom (across a horizontal line)
+    FlipVertical,   // mirrored left to right (across a vertical line)
+}
+
+// impl ability to parse from str
+impl FromStr for Transform {
+    // err to return if parsing fails
+    type Err = ();
+
+    // fn to parse transform from str
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "rotate" => Ok(Self::Rotate),
+            "flip-horizontal" => Ok(Self::FlipHorizontal),
+            "flip-vertical" => Ok(Self::FlipVertical),
+            _ => Err(()),
+        }
+    }
+}
+
+// enum to store what opening a flagged square does
+#[derive(Clone, Copy, PartialEq)]
+pub enum OpenFlagged {
+    Ignore, // nothing (the flag protects the square)
+    Warn,   // nothing, but the player is told the square is flagged
+    Unflag, // the flag is removed, and the square opened
+}
+
+// impl ability to parse from str
+impl FromStr for OpenFlagged {
+    // err to return if parsing fails
+    type Err = ();
+
+    // fn to parse open flagged setting from str
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "ignore" => Ok(Self::Ignore),
+            "warn" => Ok(Self::Warn),
+            "unflag" => Ok(Self::Unflag),
+            _ => Err(()),
+        }
+    }
+}
+
+// enum to store which squares to open at the start of a game, for a foothold without guessing
+#[derive(Clone, Copy, PartialEq)]
+pub enum PreOpen {
+    Squares(usize), // this many random safe squares
+    Zero,           // one random safe square with no mines near (so that it cascades)
+    Corner,         // the safest corner (one which isn't a mine, with the fewest mines near)
+}
+
+// impl ability to parse from str
+impl FromStr for PreOpen {
+    // err to return if parsing fails
+    type Err = std::num::ParseIntError;
+
+    // fn to parse pre-open setting from str: "zero", "corner", or a number of squares
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "zero" => Ok(Self::Zero),
+            "corner" => Ok(Self::Corner),
+            number => number.parse::<usize>().map(Self::Squares),
+        }
+    }
+}
+
+// enum to store what a player can see of a square (used by each way of displaying the board)
+#[derive(Clone, Copy, PartialEq)]
+pub enum SquareView {
+    Hole,        // outside the board's shape
+    Closed,      // not opened (or a mine not yet revealed)
+    Flag,        // flagged
+    Mine,        // a revealed mine
+    Fog,         // opened by a cascade too far away to show its number (known to be safe)
+    Open(usize), // open, with this many mines near
+}
+
+// struct to store the minesweeper game
+#[derive(Clone)]
+pub struct Minesweeper {
+    width: usize,                        // width of board
+    height: usize,                       // height of board
+    mines: HashSet<Position>,            // set to store mines
+    open_squares: HashSet<Position>,     // set to store current open positions
+    flagged_squares: HashSet<Position>,  // set to store current flagged positions
+    all_squares: HashSet<Position>,      // set to store all possible positions
+    state: GameState,                    // game state (playing, won, lost)
+    variant: MinesweeperVariant,         // variant (of the left half of the board, if split)
+    split: Option<MinesweeperVariant>,   // variant of the right half of the board (if split)
+    show_adjacency: bool,                // whether to highlight neighbors of the selected square
+    hovered: Option<Position>,           // selected square to highlight neighbors of (if any)
+    show_clues: bool,                    // whether to display remaining mines needed around numbers
+    show_satisfied: bool,                // whether to dim numbers with as many flags near as mines
+    highlighted: Option<usize>,          // number to mark on every open square showing it (if any)
+    flip_y: bool,                        // whether row 1 is displayed at the bottom
+    safe_radius: Option<usize>,          // radius around first open kept free of mines
+    min_mine_spacing: Option<usize>,     // least chebyshev distance between mines (if any)
+    no_edge_mines: bool,                 // whether mines are kept off the edges of the board
+    quiet: bool,                         // whether to leave out prompts and extra messages
+    prompts: Prompts,                    // prompts to display instead of the move prompts
+    show_clusters: bool,                 // whether to display mine clusters at game end
+    show_rulers: bool,                   // whether to display mine counts of each row and col
+    show_status: bool,                   // whether to display a status line above the board
+    show_letters: bool,                  // whether cols are named (and entered) with letters
+    started: Option<Instant>,            // when the first square was opened (if one has been)
+    paused: Option<Instant>,             // when the game was paused, hiding the board (if it is)
+    pause_stops_timer: bool,             // whether time paused is left out of the time taken
+    show_guesses: bool,                  // whether to review guesses made at game end
+    warn_isolated: bool,                 // whether to warn of squares no cascade can reach
+    assist: bool,                        // whether to point out guessing and over-flagging
+    trace_solver: bool,                  // whether to write the solver's decisions to stderr
+    guesses: Vec<(Self, Position)>,      // board before each guess, and square guessed
+    no_guessing: bool,                   // whether the board is made solvable without guessing
+    technique: Option<Technique>,        // technique the no-guess board must need (if any)
+    redraw: bool,                        // whether the board is redrawn in place each move
+    debug: bool,                         // whether debug commands are available
+    easy: bool,                          // whether easy mode hints are available
+    spectate: bool,                      // whether the solver makes every move it can prove
+    auto_guess: Option<GuessStrategy>,   // how the solver guesses when spectating (if it does)
+    seed: Option<u64>,                   // seed to generate mines from (if any)
+    moves: Vec<Move>,                    // opens, flags and surrenders made, to record a replay
+    move_times: Vec<Duration>,           // time since the first open that each move was made
+    record: Option<String>,              // path to write a replay of the game to (if any)
+    history: Option<String>,             // path to write the move history to (if any)
+    html: Option<String>,                // path to write the final board as html to (if any)
+    mbf: Option<String>,                 // path to write the board as a .mbf file to (if any)
+    leaderboard: Option<String>,         // path of leaderboard file to record a win in (if any)
+    player: String,                      // name of the player, for the leaderboard
+    cascade_clears_flags: bool,          // whether cascades open (and unflag) flagged squares
+    max_cascade: Option<usize>,          // most squares a cascade can open (if limited)
+    fog: Option<usize>,                  // distance past which cascades hide numbers (if any)
+    fogged: HashSet<Position>,           // squares opened by cascades with their numbers hidden
+    open_flagged: OpenFlagged,           // what opening a flagged square does
+    viewport: Option<(usize, usize)>,    // size of window of board to display (if any)
+    minimap_scale: usize,                // squares on a side of each block shown by the minimap
+    detective: bool,                     // whether flagged mines show how many mines are near
+    wrong_flags_lose: bool,              // whether flagging a safe square loses (detective only)
+    flag_to_win: bool,                   // whether every mine must be flagged to win
+    win_condition: Option<WinCondition>, // custom check for a win, replacing the usual (if any)
+    explore: bool,                       // whether mines can be opened and flagged after a win
+    theme: Theme,                        // glyphs, colors and terminology to display with
+    lives: Option<usize>,                // lives left, one lost per mine opened (if any)
+    helps: Option<usize>,                // helps left, each opening random safe squares (if any)
+    pre_opened: bool,                    // whether squares were opened for the player at the start
+    last_move: Option<Position>,         // position of last move made (if any)
+    last_move_type: Option<MoveType>,    // type of last move made (if any), to repeat it
+    longest_cascade: usize,              // most squares opened by a single move
+    direct_opens: usize,                 // squares opened directly by the player's moves
+    cascade_opens: usize,                // squares opened by cascades from the player's moves
+    autosave: Option<String>,            // path to save game to after every move (if any)
+    animate: Option<Duration>,           // delay between revealing each mine on a loss (if any)
+    revealed: Option<HashSet<Position>>, // mines revealed so far while animating a loss
+    notices: Vec<String>,                // messages for the player, displayed with the board
+}
+
+impl Minesweeper {
+    // fn to construct a new game from an instance of GameSettings
+    // mines are placed from the seed setting if there is one, otherwise from a random seed,
+    // which is kept as the game's seed (so that every game can be replayed)
+    pub fn new(settings: GameSettings) -> Self {
+        let seed = settings.seed.unwrap_or_else(random_seed);
+        Self::new_with_seeded_rng(
+            GameSettings {
+                seed: Some(seed),
+                ..settings
+            },
+            &mut SeededRng::new(seed),
+        )
+    }
+
+    // fn to construct a new game from an instance of GameSettings, using the given rand rng
+    // to place mines (so that a seeded rng always gives the same board). the seed drawn from
+    // the rng is kept as the game's seed, so the game can be replayed
+    #[cfg(feature = "rand")]
+    pub fn new_with_rng(settings: GameSettings, rng: &mut impl Rng) -> Self {
+        let seed = rng.gen();
+        Self::new_with_seeded_rng(
+            GameSettings {
+                seed: Some(seed),
+                ..settings
+            },
+            &mut SeededRng::new(seed),
+        )
+    }
+
+    // fn to construct a new game from an instance of GameSettings, using the game's own rng
+    // to place mines
+    // (and to choose squares to open at the start, if asked to)
+    fn new_with_seeded_rng(settings: GameSettings, rng: &mut SeededRng) -> Self {
+        // squares mines can be placed on, and notices for the player about placing them
+        let mut mine_squares = Self::settings_squares(&settings);
+        let num_mines = settings.num_mines;
+        let mut notices = Vec::new();
+        // keep mines off the edges of the board if asked to, unless the rest can't hold them
+        if settings.no_edge_mines {
+            let (width, height) = (settings.board_width, settings.board_height);
+            let interior = Self::interior_squares(&mine_squares, width, height);
+            if interior.len() >= num_mines {
+                mine_squares = interior;
+            } else {
+                notices.push(format!(
+                    "Warning: unable to fit {num_mines} mines away from the edges."
+                ));
+            }
+        }
+        let spacing = settings.min_mine_spacing;
+        let mines =
+            Self::generate_mines_apart(&mine_squares, num_mines, spacing, rng, &mut notices);
+        let (transforms, pre_open) = (settings.transforms.clone(), settings.pre_open);
+        let mut minesweeper = Self::new_with_mines(settings, mines);
+        minesweeper.notices = notices;
+        for transform in transforms {
+            minesweeper.apply_transform(transform);
+        }
+        if let Some(pre_open) = pre_open {
+            minesweeper.pre_open(pre_open, rng);
+        }
+        minesweeper
+    }
+
+    // fn to find the safest corner of the board to open first: one which isn't a mine, with
+    // the fewest mines near (so it is the most likely to cascade), first in reading order on
+    // ties. corners outside the board's shape are left out, and if every corner is a mine, the
+    // one with the fewest mines near is still chosen (for its mine to be moved elsewhere)
+    fn safest_corner(&self) -> Option<Position> {
+        let (right, bottom) = (self.width.saturating_sub(1), self.height.saturating_sub(1));
+        [(0, 0), (right, 0), (0, bottom), (right, bottom)]
+            .into_iter()
+            .filter(|pos| self.all_squares.contains(pos))
+            .min_by_key(|&(x, y)| (self.mines.contains(&(x, y)), self.mines_near(x, y), y, x))
+    }
+
+    // fn to open random safe squares at the start of a game
+    // squares are opened one at a time, as the first open can move mines (to keep it safe).
+    // squares whose opening would win the game are skipped, and if there are no squares left
+    // to open, fewer are opened than asked for.
+    fn pre_open(&mut self, pre_open: PreOpen, rng: &mut SeededRng) {
+        let amount = match pre_open {
+            PreOpen::Squares(amount) => amount,
+            PreOpen::Zero => 1,
+            // the safest corner is chosen rather than a random square
+            PreOpen::Corner => {
+                if let Some((x, y)) = self.safest_corner() {
+                    let mut game = self.clone();
+                    // every corner is a mine, so move this one away first
+                    game.clear_safe_area(x, y, 0);
+                    // ignore err as corners are on the board
+                    let _ = game.open(x, y);
+                    if game.state == GameState::Playing {
+                        *self = game;
+                    }
+                }
+                return;
+            }
+        };
+        for _ in 0..amount {
+            // find closed safe squares (with no mines near, if asked for a cascade)
+            // sorted so that the squares chosen only depend on the rng
+            let mut candidates: Vec<Position> = self
+                .all_squares
+                .iter()
+                .filter(|&pos| !self.mines.contains(pos) && !self.open_squares.contains(pos))
+                .filter(|&&(x, y)| pre_open != PreOpen::Zero || self.mines_near(x, y) == 0)
+                .copied()
+                .collect();
+            candidates.sort_unstable();
+            // open candidates in random order on a copy of the board until one doesn't win,
+            // stopping if none are left
+            let Some(opened) = rng
+                .choose_multiple(&candidates, candidates.len())
+                .into_iter()
+                .find_map(|(x, y)| {
+                    let mut game = self.clone();
+                    // ignore err as candidates are on the board
+                    let _ = game.open(x, y);
+                    (game.state == GameState::Playing).then_some(game)
+                })
+            else {
+                break;
+            };
+            *self = opened;
+        }
+    }
+
+    // fn to use up a help, opening HELP_SQUARES random closed, unflagged safe squares
+    // (or as many as there are) and recording them as moves. returns the number of squares
+    // opened, including any cascades
+    fn help(&mut self, rng: &mut SeededRng) -> usize {
+        self.helps = self.helps.map(|helps| helps.saturating_sub(1));
+        let mut opened = 0;
+        for _ in 0..HELP_SQUARES {
+            // find closed safe squares afresh each time, as the first open of the game can
+            // move mines, and cascades can open squares. sorted so that the squares chosen
+            // only depend on the rng
+            let mut candidates: Vec<Position> = self
+                .closed_cells()
+                .filter(|pos| !self.mines.contains(pos))
+                .collect();
+            candidates.sort_unstable();
+            // guard to check if the game is over, or there are no squares left to open
+            if self.state != GameState::Playing || candidates.is_empty() {
+                break;
+            }
+            let (x, y) = candidates[rng.gen_index(candidates.len())];
+            // ignore err as candidates are on the board
+            let cascade = self.open(x, y).unwrap_or(0);
+            self.longest_cascade = self.longest_cascade.max(cascade);
+            self.record_move(MoveType::Open, x, y);
+            opened += cascade;
+        }
+        opened
+    }
+
+    // fn to get the squares on the board for an instance of GameSettings:
+    // the board shape if given, else a rectangle
+    fn settings_squares(settings: &GameSettings) -> HashSet<Position> {
+        settings.shape.clone().unwrap_or_else(|| {
+            Self::generate_all_squares(settings.board_width, settings.board_height)
+        })
+    }
+
+    // fn to get the squares of a board which aren't on its edges
+    fn interior_squares(
+        squares: &HashSet<Position>,
+        width: usize,
+        height: usize,
+    ) -> HashSet<Position> {
+        squares
+            .iter()
+            .filter(|&&(x, y)| x > 0 && y > 0 && x + 1 < width && y + 1 < height)
+            .copied()
+            .collect()
+    }
+
+    // fn to get the squares mines can be placed on: every square, or only those away from the
+    // edges if asked to (and if they can hold every mine, as when the game was generated)
+    fn mine_squares(&self, num_mines: usize) -> HashSet<Position> {
+        let interior = Self::interior_squares(&self.all_squares, self.width, self.height);
+        match self.no_edge_mines && interior.len() >= num_mines {
+            true => interior,
+            false => self.all_squares.clone(),
+        }
+    }
+
+    // fn to construct a new game from an instance of GameSettings, with the given mines
+    // (the number of mines setting is ignored)
+    pub fn new_with_mines(settings: GameSettings, mines: HashSet<Position>) -> Self {
+        let all_squares = Self::settings_squares(&settings);
+        Self {
+            width: settings.board_width,
+            height: settings.board_height,
+            mines,
+            open_squares: HashSet::<Position>::new(), // init
+            flagged_squares: HashSet::<Position>::new(), // init
+            all_squares,
+            state: GameState::Playing, // init
+            variant: settings.variant,
+            split: settings.split_variant,
+            show_adjacency: false, // init
+            hovered: None,         // init
+            show_clues: false,     // init
+            show_satisfied: false, // init
+            highlighted: None,     // init
+            flip_y: settings.flip_y,
+            safe_radius: settings.safe_radius,
+            min_mine_spacing: settings.min_mine_spacing,
+            no_edge_mines: settings.no_edge_mines,
+            quiet: settings.quiet,
+            prompts: settings.prompts,
+            show_clusters: settings.show_clusters,
+            show_rulers: settings.show_rulers,
+            show_status: settings.show_status,
+            show_letters: settings.show_letters,
+            started: None, // init
+            paused: None,  // init
+            pause_stops_timer: settings.pause_stops_timer,
+            show_guesses: settings.show_guesses,
+            warn_isolated: settings.warn_isolated,
+            assist: settings.assist,
+            trace_solver: settings.trace_solver,
+            guesses: Vec::new(), // init
+            no_guessing: settings.no_guessing,
+            technique: settings.required_technique,
+            redraw: settings.redraw,
+            debug: settings.debug,
+            easy: settings.easy,
+            spectate: settings.spectate,
+            auto_guess: settings.auto_guess,
+            seed: settings.seed,
+            moves: Vec::new(),      // init
+            move_times: Vec::new(), // init
+            record: settings.record,
+            history: settings.history,
+            html: settings.html,
+            mbf: settings.mbf,
+            cascade_clears_flags: settings.cascade_clears_flags,
+            max_cascade: settings.max_cascade,
+            fog: settings.fog,
+            fogged: HashSet::<Position>::new(), // init
+            open_flagged: settings.open_flagged,
+            viewport: settings.viewport,
+            minimap_scale: settings.minimap_scale,
+            detective: settings.detective,
+            wrong_flags_lose: settings.wrong_flags_lose,
+            flag_to_win: settings.flag_to_win,
+            win_condition: settings.win_condition,
+            explore: settings.explore,
+            leaderboard: settings.leaderboard,
+            player: settings.player,
+            theme: settings.theme,
+            lives: settings.lives,
+            pre_opened: settings.pre_open.is_some(),
+            helps: settings.helps,
+            last_move: None,      // init
+            last_move_type: None, // init
+            longest_cascade: 0,   // init
+            direct_opens: 0,      // init
+            cascade_opens: 0,     // init
+            autosave: settings.autosave,
+            animate: settings.animate,
+            revealed: None,      // init
+            notices: Vec::new(), // init
+        }
+    }
+
+    // fn to get the width of the board
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    // fn to get the height of the board
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    // fn to get the number of mines on the board
+    pub fn mine_count(&self) -> usize {
+        self.mines.len()
+    }
+
+    // fn to get the game's variant
+    pub fn variant(&self) -> MinesweeperVariant {
+        self.variant
+    }
+
+    // fn to get the game's state (playing, won, lost)
+    pub fn state(&self) -> GameState {
+        self.state
+    }
+
+    // fn to iterate over the open squares, with the number of mines near each
+    // (in no particular order)
+    pub fn open_cells(&self) -> impl Iterator<Item = (Position, usize)> + '_ {
+        self.open_squares
+            .iter()
+            .map(|&(x, y)| ((x, y), self.mines_near(x, y)))
+    }
+
+    // fn to iterate over the flagged squares (in no particular order)
+    pub fn flagged_cells(&self) -> impl Iterator<Item = Position> + '_ {
+        self.flagged_squares.iter().copied()
+    }
+
+    // fn to iterate over the closed squares which aren't flagged (in no particular order)
+    // together with open_cells and flagged_cells, this covers every square on the board
+    pub fn closed_cells(&self) -> impl Iterator<Item = Position> + '_ {
+        self.all_squares
+            .iter()
+            .filter(|&pos| !self.open_squares.contains(pos) && !self.flagged_squares.contains(pos))
+            .copied()
+    }
+
+    // fn to save the game to a string
+    // the save format is one field per line: the board size, variant, game state,
+    // and the mines, open squares, flagged squares and squares outside the board's shape
+    // as space-separated x,y positions.
+    // display and generation options aren't saved.
+    fn save_to_string(&self) -> String {
+        // fn to format a set of positions (sorted so that saves of the same game are identical)
+        let format_positions = |positions: &HashSet<Position>| {
+            let mut positions: Vec<&Position> = positions.iter().collect();
+            positions.sort();
+            positions
+                .iter()
+                .map(|(x, y)| format!(" {x},{y}"))
+                .collect::<String>()
+        };
+        // the split variant, lives and helps are only saved if playing with them
+        let split = self.split.map_or(String::new(), |split| {
+            format!("split-variant {}\n", split.name())
+        });
+        let lives = self
+            .lives
+            .map_or(String::new(), |lives| format!("lives {lives}\n"));
+        let helps = self
+            .helps
+            .map_or(String::new(), |helps| format!("helps {helps}\n"));
+        let optional = split + &lives + &helps;
+        format!(
+            "size {} {}\nvariant {}\nstate {}\nmines{}\nopen{}\nflagged{}\nholes{}\n{optional}",
+            self.width,
+            self.height,
+            self.variant.name(),
+            self.state.name(),
+            format_positions(&self.mines),
+            format_positions(&self.open_squares),
+            format_positions(&self.flagged_squares),
+            format_positions(
+                &Self::generate_all_squares(self.width, self.height)
+                    .difference(&self.all_squares)
+                    .copied()
+                    .collect()
+            ),
+        )
+    }
+
+    // fn to load a game from a string produced by save_to_string
+    // display and generation options are taken from the settings given, as they aren't saved.
+    fn load_from_string(save: &str, settings: GameSettings) -> Result<Self, LoadError> {
+        // map each field name to its value
+        let fields: HashMap<&str, &str> = save
+            .lines()
+            .map(|line| line.split_once(' ').unwrap_or((line, "")))
+            .collect();
+        // fn to get a field's value, erring if missing
+        let field = |name: &str| fields.get(name).copied().ok_or(LoadError::Malformed);
+
+        // parse board size
+        let (raw_width, raw_height) = field("size")?.split_once(' ').ok_or(LoadError::Malformed)?;
+        let (width, height) =
+            Self::validate_size(raw_width, raw_height).map_err(|_| LoadError::Malformed)?;
+        // fn to parse a set of positions, erring if any lie outside the board
+        let parse_positions = |name: &str| {
+            field(name)?
+                .split_whitespace()
+                .map(|raw_pos| {
+                    let (raw_x, raw_y) = raw_pos.split_once(',').ok_or(LoadError::Malformed)?;
+                    match (raw_x.parse::<usize>(), raw_y.parse::<usize>()) {
+                        (Ok(x), Ok(y)) if x < width && y < height => Ok((x, y)),
+                        _ => Err(LoadError::Malformed),
+                    }
+                })
+                .collect::<Result<HashSet<Position>, LoadError>>()
+        };
+
+        // build game with saved size and variant, then fill in saved state
+        let mut game = Self::new(GameSettings {
+            board_width: width,
+            board_height: height,
+            num_mines: 0,
+            pre_open: None,
+            transforms: Vec::new(),
+            variant: field("variant")?
+                .parse()
+                .map_err(|_| LoadError::Malformed)?,
+            // the split variant is optional, as it is only saved if the board is split
+            split_variant: fields
+                .get("split-variant")
+                .map(|split| split.parse())
+                .transpose()
+                .map_err(|_| LoadError::Malformed)?,
+            ..settings
+        });
+        game.state = field("state")?.parse()?;
+        game.mines = parse_positions("mines")?;
+        game.open_squares = parse_positions("open")?;
+        game.flagged_squares = parse_positions("flagged")?;
+        // holes are optional, as saves from before custom shapes don't have them
+        if fields.contains_key("holes") {
+            for hole in parse_positions("holes")? {
+                game.all_squares.remove(&hole);
+            }
+        }
+        // lives are optional, as they are only saved if playing with lives
+        if let Some(lives) = fields.get("lives") {
+            game.lives = Some(lives.parse().map_err(|_| LoadError::Malformed)?);
+        }
+        // helps are optional too, for the same reason
+        if let Some(helps) = fields.get("helps") {
+            game.helps = Some(helps.parse().map_err(|_| LoadError::Malformed)?);
+        }
+        // fix the saved state if it doesn't match the squares
+        game.reconcile_state();
+        // check the saved squares could have come from a real game
+        let inconsistent = game.inconsistent_squares();
+        if !inconsistent.is_empty() {
+            return Err(LoadError::Inconsistent(inconsistent));
+        }
+        // return loaded game
+        Ok(game)
+    }
+
+    // fn to encode the board as a .mbf board file, as read by other minesweeper programs and
+    // solvers: a byte each for the width and height, the number of mines as a big-endian u16,
+    // then a byte each for the x and y of every mine. only the mines are kept, so the board's
+    // shape and the squares opened and flagged are lost.
+    // errs if the board is wider or taller than MBF_MAX_SIZE (eg. after resizing)
+    pub fn to_mbf(&self) -> Result<Vec<u8>, MbfSizeError> {
+        // guard to check the board's size fits in a byte (so its mine count fits in a u16)
+        if self.width.max(self.height) > MBF_MAX_SIZE {
+            return Err(MbfSizeError);
+        }
+        // sort mines so that files of the same board are identical
+        let mut mines: Vec<&Position> = self.mines.iter().collect();
+        mines.sort();
+        let mut mbf = vec![self.width as u8, self.height as u8];
+        mbf.extend((mines.len() as u16).to_be_bytes());
+        mbf.extend(mines.iter().flat_map(|&&(x, y)| [x as u8, y as u8]));
+        Ok(mbf)
+    }
+
+    // fn to load a board from a .mbf board file produced by to_mbf (or another program)
+    // the board is played from the start, with options taken from the settings given
+    pub fn from_mbf(mbf: &[u8], settings: GameSettings) -> Result<Self, LoadError> {
+        // guard to check the header is there and the board isn't empty
+        let [width, height, count_high, count_low, positions @ ..] = mbf else {
+            return Err(LoadError::Malformed);
+        };
+        let (width, height) = (usize::from(*width), usize::from(*height));
+        let num_mines = usize::from(u16::from_be_bytes([*count_high, *count_low]));
+        if width == 0 || height == 0 || positions.len() != num_mines * 2 {
+            return Err(LoadError::Malformed);
+        }
+        // parse mines, erring if any lie outside the board or are given twice
+        let mines: HashSet<Position> = positions
+            .chunks(2)
+            .map(|pos| (usize::from(pos[0]), usize::from(pos[1])))
+            .collect();
+        if mines.len() != num_mines || mines.iter().any(|&(x, y)| x >= width || y >= height) {
+            return Err(LoadError::Malformed);
+        }
+        // return game with the file's mines, on a rectangular board of its size
+        let settings = GameSettings {
+            board_width: width,
+            board_height: height,
+            shape: None,
+            ..settings
+        };
+        Ok(Self::new_with_mines(settings, mines))
+    }
+
+    // fn to load a board given only by its numbers, as a puzzle designed by its clues:
+    // each line is a row of the board, with a digit for each open square showing that number
+    // and any other char (eg. .) for each closed square. the fewest mines which explain the
+    // numbers are placed, with options taken from the settings given
+    fn from_clues(clues: &str, settings: GameSettings) -> Result<Self, LoadError> {
+        let rows: Vec<&str> = clues.lines().collect();
+        let height = rows.len();
+        let width = rows.first().map_or(0, |row| row.chars().count());
+        // guard to check the board isn't empty and every row is the same width
+        if width == 0 || rows.iter().any(|row| row.chars().count() != width) {
+            return Err(LoadError::Malformed);
+        }
+        // find the number on each open square
+        let numbers: HashMap<Position, usize> = rows
+            .iter()
+            .enumerate()
+            .flat_map(|(y, row)| {
+                row.chars()
+                    .enumerate()
+                    .filter_map(move |(x, c)| Some(((x, y), c.to_digit(10)? as usize)))
+            })
+            .collect();
+        // place mines explaining the numbers, then open the numbered squares
+        let settings = GameSettings {
+            board_width: width,
+            board_height: height,
+            shape: None,
+            ..settings
+        };
+        let mut game = Self::new_with_mines(settings, HashSet::new());
+        game.mines = game
+            .infer_mines_from_numbers(&numbers)
+            .ok_or(LoadError::Malformed)?;
+        game.open_squares = numbers.into_keys().collect();
+        game.reconcile_state();
+        Ok(game)
+    }
+
+    // fn to set the game's state from its squares (used when loading saves, whose state may be
+    // wrong): an opened mine means the game is lost, and squares which win the game (as checked
+    // by determine_win) mean it is won. otherwise a saved loss is kept (hitting a mine or
+    // surrendering leaves no opened mine), but a saved win isn't, as the squares don't win.
+    fn reconcile_state(&mut self) {
+        self.state = if !self.open_squares.is_disjoint(&self.mines) {
+            GameState::Lost
+        } else if self.win_reached() {
+            GameState::Won
+        } else if self.state == GameState::Lost {
+            GameState::Lost
+        } else {
+            GameState::Playing
+        };
+    }
+
+    // fn to find squares whose state contradicts the rules of the game (used to validate saves)
+    // these are squares outside the board's shape that aren't empty, and flagged open squares.
+    // (opened mines and unfinished wins are fixed by reconciling the game's state instead)
+    // returns the squares in reading order
+    fn inconsistent_squares(&self) -> Vec<Position> {
+        let mut squares = HashSet::<Position>::new();
+        // squares outside the board's shape can't hold anything
+        squares.extend(
+            self.mines
+                .iter()
+                .chain(&self.open_squares)
+                .copied()
+                .chain(self.flagged_cells())
+                .filter(|pos| !self.all_squares.contains(pos)),
+        );
+        // open squares can't be flagged
+        squares.extend(self.open_squares.intersection(&self.flagged_squares));
+        // sort for deterministic output
+        let mut squares: Vec<Position> = squares.into_iter().collect();
+        squares.sort_by_key(|&(x, y)| (y, x));
+        squares
+    }
+
+    // fn to write the game to the autosave file (if any)
+    // failing to write only warns, so that a full disk etc. doesn't end the game
+    fn autosave(&self) {
+        if let Some(path) = &self.autosave {
+            if let Err(err) = std::fs::write(path, self.save_to_string()) {
+                println!("Warning: unable to autosave to {path}: {err}");
+            }
+        }
+    }
+
+    // fn to generate a random set of mines among the given squares, using the given rng
+    // (so that the same seed always generates the same mines)
+    fn generate_mines_with(
+        squares: &HashSet<Position>,
+        num_mines: usize,
+        rng: &mut SeededRng,
+    ) -> HashSet<Position> {
+        // sort squares so that the mines chosen only depend on the rng
+        let mut squares: Vec<Position> = squares.iter().copied().collect();
+        squares.sort_unstable();
+        // choose distinct random squares to be mines
+        rng.choose_multiple(&squares, num_mines)
+            .into_iter()
+            .collect()
+    }
+
+    // fn to generate a random set of mines among the given squares, at least spacing squares
+    // apart from each other (by chebyshev distance, so a spacing of 2 means no mines are next
+    // to each other). mines are chosen by going through the squares in a random order and
+    // rejecting any too close to mines already chosen. it is retried if the squares run out
+    // first, and returns none if that keeps happening (there may be no way to fit them)
+    fn generate_spaced_mines(
+        squares: &HashSet<Position>,
+        num_mines: usize,
+        spacing: usize,
+        rng: &mut SeededRng,
+    ) -> Option<HashSet<Position>> {
+        // sort squares so that the mines chosen only depend on the rng
+        let mut squares: Vec<Position> = squares.iter().copied().collect();
+        squares.sort_unstable();
+        (0..MAX_SPACING_TRIES).find_map(|_| {
+            let mut mines = Vec::new();
+            for square in rng.choose_multiple(&squares, squares.len()) {
+                if mines.len() == num_mines {
+                    break;
+                }
+                if mines
+                    .iter()
+                    .all(|&mine| chebyshev_distance(mine, square) >= spacing)
+                {
+                    mines.push(square);
+                }
+            }
+            (mines.len() == num_mines).then(|| mines.into_iter().collect())
+        })
+    }
+
+    // fn to generate a random set of mines among the given squares, spaced apart if asked to
+    // (if they can't be spaced that far apart, they are placed without spacing, adding a
+    // notice saying so)
+    fn generate_mines_apart(
+        squares: &HashSet<Position>,
+        num_mines: usize,
+        spacing: Option<usize>,
+        rng: &mut SeededRng,
+        notices: &mut Vec<String>,
+    ) -> HashSet<Position> {
+        match spacing {
+            Some(spacing) => Self::generate_spaced_mines(squares, num_mines, spacing, rng)
+                .unwrap_or_else(|| {
+                    notices.push(format!(
+                        "Warning: unable to space {num_mines} mines {spacing} apart."
+                    ));
+                    Self::generate_mines_with(squares, num_mines, rng)
+                }),
+            None => Self::generate_mines_with(squares, num_mines, rng),
+        }
+    }
+
+    // fn to check if a square is far enough from every mine to be one itself
+    // (always true unless mines are kept a minimum distance apart)
+    fn is_spaced(&self, pos: Position) -> bool {
+        match self.min_mine_spacing {
+            Some(spacing) => self
+                .mines
+                .iter()
+                .all(|&mine| chebyshev_distance(mine, pos) >= spacing),
+            None => true,
+        }
+    }
+
+    // fn to generate the set of all positions for a board of the given dimensions
+    fn generate_all_squares(width: usize, height: usize) -> HashSet<Position> {
+        let mut all_squares = HashSet::<Position>::new();
+        // loop through all positions and add them to the set
+        for x in 0..width {
+            for y in 0..height {
+                all_squares.insert((x, y));
+            }
+        }
+        // return all squares
+        all_squares
+    }
+
+    // fn to remap every square on the board to a new board size
+    // (used to rotate and reflect the board: mapping must be one-to-one onto the new board)
+    fn remap_squares(&mut self, width: usize, height: usize, map: impl Fn(Position) -> Position) {
+        let remap = |squares: &HashSet<Position>| squares.iter().map(|&pos| map(pos)).collect();
+        self.mines = remap(&self.mines);
+        self.open_squares = remap(&self.open_squares);
+        self.fogged = remap(&self.fogged);
+        self.flagged_squares = remap(&self.flagged_squares);
+        self.all_squares = remap(&self.all_squares);
+        self.revealed = self.revealed.as_ref().map(remap);
+        self.hovered = self.hovered.map(&map);
+        self.last_move = self.last_move.map(&map);
+        (self.width, self.height) = (width, height);
+    }
+
+    // fn to rotate the board a quarter turn clockwise (swapping its width and height)
+    // blind variants are turned with it, so every number stays the same.
+    // (l-tromino has no turned variant, so its numbers change)
+    pub fn rotate_90(&mut self) {
+        use MinesweeperVariant::{BlindDown, BlindLeft, BlindRight, BlindUp};
+        let height = self.height;
+        self.remap_squares(self.height, self.width, |(x, y)| (height - 1 - y, x));
+        self.turn_variants(|variant| match variant {
+            BlindUp => BlindRight,
+            BlindRight => BlindDown,
+            BlindDown => BlindLeft,
+            BlindLeft => BlindUp,
+            variant => variant,
+        });
+    }
+
+    // fn to mirror the board top to bottom (across a horizontal line)
+    // blind variants are mirrored with it, so every number stays the same.
+    // (l-tromino has no mirrored variant, so its numbers change)
+    pub fn flip_horizontal(&mut self) {
+        use MinesweeperVariant::{BlindDown, BlindUp};
+        let height = self.height;
+        self.remap_squares(self.width, self.height, |(x, y)| (x, height - 1 - y));
+        self.turn_variants(|variant| match variant {
+            BlindUp => BlindDown,
+            BlindDown => BlindUp,
+            variant => variant,
+        });
+    }
+
+    // fn to mirror the board left to right (across a vertical line)
+    // blind variants are mirrored with it, so every number stays the same.
+    // (l-tromino has no mirrored variant, so its numbers change)
+    pub fn flip_vertical(&mut self) {
+        use MinesweeperVariant::{BlindLeft, BlindRight};
+        let width = self.width;
+        self.remap_squares(self.width, self.height, |(x, y)| (width - 1 - x, y));
+        self.turn_variants(|variant| match variant {
+            BlindLeft => BlindRight,
+            BlindRight => BlindLeft,
+            variant => variant,
+        });
+        // the halves of a split board swap sides too
+        if let Some(split) = self.split {
+            (self.variant, self.split) = (split, Some(self.variant));
+        }
+    }
+
+    // fn to turn or mirror the game's variants (both of them, if the board is split)
+    // the board is always split into left and right halves, so with a split board,
+    // rotating changes numbers, as do mirrors of boards with an odd width
+    fn turn_variants(&mut self, turn: impl Fn(MinesweeperVariant) -> MinesweeperVariant) {
+        self.variant = turn(self.variant);
+        self.split = self.split.map(turn);
+    }
+
+    // fn to rotate or reflect the board
+    fn apply_transform(&mut self, transform: Transform) {
+        match transform {
+            Transform::Rotate => self.rotate_90(),
+            Transform::FlipHorizontal => self.flip_horizontal(),
+            Transform::FlipVertical => self.flip_vertical(),
+        }
+    }
+
+    // fn to resize the board to new dimensions
+    // resizing always produces a fresh rectangular board (any custom shape is lost):
+    // mines are regenerated to keep the same mine density as the current board
+    // (rounded to the nearest mine), and all open and flagged squares are cleared.
+    // no progress is preserved, as numbers on previously opened squares would no longer be valid.
+    pub fn resize(&mut self, new_width: usize, new_height: usize) {
+        // calculate new number of mines from current density
+        let old_cells = self.all_squares.len();
+        let new_cells = new_width * new_height;
+        // round to nearest mine, but never fill more squares than exist
+        // (calculated in u128 so that a large board can't overflow the intermediate product)
+        let num_mines = (self.mines.len() as u128 * new_cells as u128 + old_cells as u128 / 2)
+            .checked_div(old_cells as u128)
+            .unwrap_or(0)
+            .min(new_cells as u128) as usize;
+
+        // rebuild board with new dimensions
+        self.width = new_width;
+        self.height = new_height;
+        self.all_squares = Self::generate_all_squares(new_width, new_height);
+        let mut rng = SeededRng::from_entropy();
+        self.mines = Self::generate_mines_apart(
+            &self.mine_squares(num_mines),
+            num_mines,
+            self.min_mine_spacing,
+            &mut rng,
+            &mut self.notices,
+        );
+        // reset game progress
+        self.open_squares.clear();
+        self.fogged.clear();
+        self.flagged_squares.clear();
+        self.state = GameState::Playing;
+        self.last_move = None;
+    }
+
+    // fn to get the variant whose neighbor rules a square in the given col uses
+    // this is the game's variant, unless the board is split, in which case the right half
+    // (cols from halfway across, so the middle col of an odd width is on the left) uses
+    // the split variant
+    fn variant_at(&self, x: usize) -> MinesweeperVariant {
+        match self.split {
+            Some(split) if x >= self.width.div_ceil(2) => split,
+            _ => self.variant,
+        }
+    }
+
+    // fn to change the variant for the rest of the game (also ending any split between variants)
+    // numbers and cascades are worked out from the variant each time they are needed, so
+    // nothing else has to change: open squares show their new numbers straight away, though
+    // squares opened by cascades may now be next to closed squares
+    fn change_variant(&mut self, variant: MinesweeperVariant) {
+        self.variant = variant;
+        self.split = None;
+    }
+
+    // fn to get the name of the game's variant, or of both variants if the board is split,
+    // eg. "normal:knight-paths"
+    fn variant_name(&self) -> String {
+        match self.split {
+            Some(split) => format!("{}:{}", self.variant().name(), split.name()),
+            None => self.variant().name().to_owned(),
+        }
+    }
+
+    // fn to get the neighbor offsets of a square in the given col
+    // (as specified by the game's variant, or the variant of its half if the board is split)
+    fn offsets(&self, x: usize) -> Vec<(isize, isize)> {
+        self.variant_at(x).offsets()
+    }
+
+    // fn to lazily generate neighbors (as specified by the game's variant) for a cell on the grid
+    pub fn neighbors_iter(&self, x: usize, y: usize) -> impl Iterator<Item = Position> + '_ {
+        // loop over individual x and y offsets
+        self.offsets(x).into_iter().filter_map(move |(dx, dy)| {
+            // apply offsets to cell specified to get neighbor
+            // checked arithmetic means offsets past either end of usize are ignored
+            let (nx, ny) = (x.checked_add_signed(dx)?, y.checked_add_signed(dy)?);
+            // check if generated neighbor lies outside game's shape and if so ignore it
+            self.all_squares.contains(&(nx, ny)).then_some((nx, ny))
+        })
+    }
+
+    // fn to generate list of neighbors (as specified by the game's variant) for a cell on the grid
+    pub fn neighbors(&self, x: usize, y: usize) -> Vec<Position> {
+        self.neighbors_iter(x, y).collect()
+    }
+
+    // fn to get the number of neighbors of a cell which are mines
+    fn mines_near(&self, x: usize, y: usize) -> usize {
+        self.neighbors_iter(x, y) // get neighbors
+            .filter(|neighbor| self.mines.contains(neighbor)) // filter to get only those which are mines
+            .count() // count the number
+    }
+
+    // fn to get the number of neighbors of a cell which are flagged
+    // (counted the same way as mines, so counts can be compared directly)
+    fn flags_near(&self, x: usize, y: usize) -> usize {
+        self.neighbors_iter(x, y) // get neighbors
+            .filter(|neighbor| self.flagged_squares.contains(neighbor)) // filter to get only flags
+            .count() // count the number
+    }
+
+    // fn to find the open numbers with as many flags near as mines (so they need no more
+    // attention, if the flags are right), in reading order. fogged numbers are left out,
+    // as they aren't shown
+    fn satisfied_numbers(&self) -> Vec<Position> {
+        let mut satisfied: Vec<Position> = self
+            .open_cells()
+            .filter(|(pos, _)| !self.fogged.contains(pos))
+            .filter(|&((x, y), mines_near)| mines_near > 0 && self.flags_near(x, y) == mines_near)
+            .map(|(pos, _)| pos)
+            .collect();
+        satisfied.sort_by_key(|&(x, y)| (y, x));
+        satisfied
+    }
+
+    // fn to open a square
+    // opening a square adds it to the current set of open squares
+    // if it is not already there and it is not flagged (as being a mine)
+    // and opens neighboring squares repeatedly as long as they are empty.
+    // if a square is opened which contains a mine, the game is lost.
+    // returns the number of squares opened (the size of the cascade),
+    // or errs if the square is outside the board's shape.
+    // with fog, squares the cascade opens too far from this one are fogged, hiding their
+    // numbers until they are opened directly (which counts as opening one square)
+    pub fn open(&mut self, x: usize, y: usize) -> Result<usize, OutOfBounds> {
+        // guard to check if square is outside board's shape
+        if !self.all_squares.contains(&(x, y)) {
+            return Err(OutOfBounds);
+        }
+        // guard to check if square was opened under fog, in which case its number is shown
+        if self.fogged.remove(&(x, y)) {
+            return Ok(1);
+        }
+        // guard to check if square has already been opened
+        if self.open_squares.contains(&(x, y)) {
+            return Ok(0);
+        }
+        // guard to check if square is flagged, unless opening removes the flag
+        if self.flagged_squares.contains(&(x, y)) {
+            if self.open_flagged != OpenFlagged::Unflag {
+                return Ok(0);
+            }
+            self.flagged_squares.remove(&(x, y));
+        }
+        // once the game is won, opening a mine just reveals it (so the board can be explored
+        // without losing). safe squares can be left closed by a custom win condition, and are
+        // opened as usual
+        if self.state == GameState::Won && self.mines.contains(&(x, y)) {
+            self.revealed
+                .get_or_insert_with(HashSet::new)
+                .insert((x, y));
+            return Ok(0);
+        }
+        // if this is the first open, regenerate mines so the board can be solved without
+        // guessing if asked to, otherwise clear mines from the safe area if one is guaranteed
+        if self.open_squares.is_empty() {
+            if self.no_guessing {
+                self.generate_no_guess_mines(x, y);
+            } else if let Some(radius) = self.safe_radius {
+                self.clear_safe_area(x, y, radius);
+            }
+        }
+        // start timing the game from its first open (or the first open after loading a save)
+        self.started.get_or_insert_with(Instant::now);
+        // if square is a mine, lose a life (flagging the mine) if there are any to spare,
+        // otherwise lose the game
+        if self.mines.contains(&(x, y)) {
+            match self.lives {
+                Some(lives) if lives > 1 => {
+                    self.lives = Some(lives - 1);
+                    self.flagged_squares.insert((x, y));
+                }
+                _ => {
+                    self.lives = self.lives.map(|_| 0);
+                    self.state = GameState::Lost;
+                }
+            }
+            return Ok(0);
+        }
+
+        // by this point, we are safe to open this square
+        // add this square to the set of open squares
+        self.open_squares.insert((x, y));
+        let mut opened = 1;
+
+        // open neighboring squares of squares with zero mines near, spreading out from this one
+        // (this is done with a queue rather than recursively, as a cascade across a large board
+        // with few mines would overflow the stack).
+        // if cascades are limited, the cascade stops once it has opened as many as it can
+        let origin = (x, y);
+        let mut queue = VecDeque::from([(x, y)]);
+        'cascade: while let Some((x, y)) = queue.pop_front() {
+            // guard to check if this square has more than zero mines surrounding it
+            if self.mines_near(x, y) > 0 {
+                continue;
+            }
+            // open all neighbors (none can be mines, as this square has zero mines near)
+            for (new_x, new_y) in self.neighbors(x, y) {
+                // guard to check if the cascade has opened as many squares as it can
+                if self
+                    .max_cascade
+                    .is_some_and(|max_cascade| opened > max_cascade)
+                {
+                    break 'cascade;
+                }
+                // flags normally block the cascade, like they block opening by hand.
+                // if cascades clear flags, remove them first: neighbors of a zero can't be mines,
+                // so any flag here is wrong. this saves opening them by hand,
+                // but the player loses the flag they placed, even if they meant it as a reminder.
+                if self.cascade_clears_flags {
+                    self.flagged_squares.remove(&(new_x, new_y));
+                }
+                // skip neighbors that are flagged or already open
+                if self.flagged_squares.contains(&(new_x, new_y))
+                    || !self.open_squares.insert((new_x, new_y))
+                {
+                    continue;
+                }
+                // hide the number of a square too far from the square opened, if fogged
+                if self
+                    .fog
+                    .is_some_and(|fog| chebyshev_distance((new_x, new_y), origin) > fog)
+                {
+                    self.fogged.insert((new_x, new_y));
+                }
+                queue.push_back((new_x, new_y));
+                opened += 1;
+            }
+        }
+        // check if opening these squares won the game
+        // (with no mines, this is any open; with one safe square, it is opening that square)
+        self.determine_win();
+        // return number of squares opened
+        Ok(opened)
+    }
+
+    // fn to move all mines within a chebyshev radius of a square elsewhere on the board
+    // mines are relocated to random squares outside the radius, so the mine count is unchanged.
+    // settings validation ensures there is always room to do this.
+    fn clear_safe_area(&mut self, x: usize, y: usize, radius: usize) {
+        // find mines in safe area
+        let unsafe_mines: Vec<Position> = self
+            .mines
+            .iter()
+            .filter(|&&mine| chebyshev_distance(mine, (x, y)) <= radius)
+            .copied()
+            .collect();
+        // find squares outside safe area which mines could be moved to
+        let mut free_squares: Vec<Position> = self
+            .mine_squares(self.mines.len())
+            .into_iter()
+            .filter(|&pos| chebyshev_distance(pos, (x, y)) > radius && !self.mines.contains(&pos))
+            .collect();
+        // sort free squares so that where mines are moved to only depends on the rng
+        // (which uses the seed setting if there is one, so seeded games can be replayed)
+        free_squares.sort_unstable();
+        let mut rng = SeededRng::new(self.seed.unwrap_or_else(random_seed));
+        // move each mine to a random free square, keeping mines apart where there is room
+        // (if the board's shape means there isn't room, remaining mines stay where they are)
+        for mine in unsafe_mines {
+            if free_squares.is_empty() {
+                break;
+            }
+            self.mines.remove(&mine);
+            let spaced: Vec<usize> = (0..free_squares.len())
+                .filter(|&index| self.is_spaced(free_squares[index]))
+                .collect();
+            let index = match spaced.is_empty() {
+                true => rng.gen_index(free_squares.len()),
+                false => spaced[rng.gen_index(spaced.len())],
+            };
+            self.mines.insert(free_squares.swap_remove(index));
+        }
+    }
+
+    // fn to regenerate mines so that the board can be solved without guessing,
+    // starting by opening the given square.
+    // candidate boards are generated from consecutive seeds, starting at the seed setting
+    // (or a random one), and checked across multiple threads. the lowest solvable seed is used,
+    // so the same seed and first open always give the same board. mines are kept away from
+    // the first open and its neighbors (and the safe area, if any) where there is room.
+    // if no solvable board is found after a maximum number of tries, mines are left as they are.
+    fn generate_no_guess_mines(&mut self, x: usize, y: usize) {
+        // find squares mines can be placed on, keeping clear of the first open where possible
+        let radius = self.safe_radius.unwrap_or(0);
+        let neighbors = self.neighbors(x, y);
+        let mine_squares = self.mine_squares(self.mines.len());
+        let mut squares: HashSet<Position> = mine_squares
+            .iter()
+            .filter(|&&pos| chebyshev_distance(pos, (x, y)) > radius && !neighbors.contains(&pos))
+            .copied()
+            .collect();
+        if squares.len() < self.mines.len() {
+            squares = mine_squares;
+            squares.remove(&(x, y));
+        }
+        // guard to check there is room for mines away from the first open
+        if squares.len() < self.mines.len() {
+            return;
+        }
+
+        // fn to generate the candidate mines for a seed
+        // (none if mines are kept apart and the seed can't space them)
+        let num_mines = self.mines.len();
+        let candidate_mines = |seed: u64| {
+            let mut rng = SeededRng::new(seed);
+            match self.min_mine_spacing {
+                Some(spacing) => {
+                    Self::generate_spaced_mines(&squares, num_mines, spacing, &mut rng)
+                }
+                None => Some(Self::generate_mines_with(&squares, num_mines, &mut rng)),
+            }
+        };
+        // fn to generate the candidate board for a seed, and check if it is solvable
+        // (if a technique is required, the board must need exactly that technique to solve)
+        let is_solvable = |seed: u64| {
+            let Some(mines) = candidate_mines(seed) else {
+                return false;
+            };
+            let mut candidate = self.clone();
+            (candidate.no_guessing, candidate.safe_radius) = (false, None);
+            candidate.mines = mines;
+            // ignore err as first open is on the board
+            let _ = candidate.open(x, y);
+            match self.technique {
+                Some(technique) => candidate.needed_technique() == Some(technique),
+                None => candidate.is_solvable(),
+            }
+        };
+
+        // check candidates across threads, each thread trying every nth seed in order.
+        // each thread stops once it passes the lowest solvable seed found so far,
+        // so every seed before the one chosen is checked.
+        let first_seed = self.seed.unwrap_or_else(random_seed);
+        // guard to check mines can be spaced apart at all, before trying every seed
+        if candidate_mines(first_seed).is_none() {
+            return;
+        }
+        let num_threads = thread::available_parallelism().map_or(1, |n| n.get());
+        let best = AtomicUsize::new(MAX_NO_GUESS_TRIES);
+        thread::scope(|scope| {
+            for thread_index in 0..num_threads {
+                let (best, is_solvable) = (&best, &is_solvable);
+                scope.spawn(move || {
+                    for index in (thread_index..MAX_NO_GUESS_TRIES).step_by(num_threads) {
+                        if index >= best.load(Ordering::Relaxed) {
+                            break;
+                        }
+                        if is_solvable(first_seed.wrapping_add(index as u64)) {
+                            best.fetch_min(index, Ordering::Relaxed);
+                        }
+                    }
+                });
+            }
+        });
+
+        // use the chosen board, reporting its seed so it can be played again
+        let index = best.into_inner();
+        let needing = self.technique.map_or(String::new(), |technique| {
+            format!(" (needing the {} technique)", technique.name())
+        });
+        if index == MAX_NO_GUESS_TRIES {
+            self.notices.push(format!(
+                "Warning: no board solvable without guessing{needing} found"
+            ));
+            return;
+        }
+        let seed = first_seed.wrapping_add(index as u64);
+        // unwrap as the chosen seed was solvable, so generated mines
+        self.mines = candidate_mines(seed).unwrap();
+        if !self.quiet {
+            self.notices.push(format!(
+                "Generated board solvable without guessing{needing} from seed {seed}"
+            ));
+        }
+    }
+
+    // fn to flag a square
+    // flagging a square makes it impossible to open.
+    // this is usually used to signal that the flagged square is probably a mine,
+    // however flagging all mines is not required to win a game.
+    // hence you cannot flag open squares as they are already proven to not be mines.
+    // errs if the square is outside the board's shape.
+    pub fn flag(&mut self, x: usize, y: usize) -> Result<(), OutOfBounds> {
+        // guard to check if square is outside board's shape
+        if !self.all_squares.contains(&(x, y)) {
+            return Err(OutOfBounds);
+        }
+        // guard to check if square is open
+        if self.open_squares.contains(&(x, y)) {
+            return Ok(());
+        }
+
+        // if we are re-flagging a flagged square, interpret that as a toggle and remove it
+        if self.flagged_squares.contains(&(x, y)) {
+            self.flagged_squares.remove(&(x, y));
+        // else add the square to the set of flagged squares
+        } else {
+            self.flagged_squares.insert((x, y));
+            // in detective mode, flagging a safe square can lose the game
+            if self.detective && self.wrong_flags_lose && !self.mines.contains(&(x, y)) {
+                self.state = GameState::Lost;
+            }
+        }
+        // flagging the last mine can win the game, if flagging is needed to win
+        self.determine_win();
+        Ok(())
+    }
+
+    // fn to check if the flags needed to win have been placed: every mine has to be flagged
+    // if flagging is needed to win, otherwise no flags are needed
+    fn flags_placed(&self) -> bool {
+        !self.flag_to_win || self.mines.is_subset(&self.flagged_squares)
+    }
+
+    // fn to find how far the player is through flagging every mine (for when flagging is
+    // needed to win): how many flags are right, which are wrong, and how many mines are left
+    pub fn flag_progress(&self) -> FlagProgress {
+        let correct = self.flagged_squares.intersection(&self.mines).count();
+        let mut incorrect: Vec<Position> = self
+            .flagged_squares
+            .difference(&self.mines)
+            .copied()
+            .collect();
+        incorrect.sort_by_key(|&(x, y)| (y, x));
+        FlagProgress {
+            correct,
+            incorrect,
+            remaining: self.mines.len() - correct,
+        }
+    }
+
+    // fn to check if all that is left to win the game is flagging mines
+    // (every safe square is open, but flagging is needed to win)
+    fn only_flags_left(&self) -> bool {
+        self.state == GameState::Playing && self.safe_squares_open()
+    }
+
+    // fn to check if every safe square is open
+    // mines can never be opened, so if the number of open squares plus the number of mines
+    // is equal to the number of all possible positions,
+    // this means that all non-mine squares have been opened.
+    fn safe_squares_open(&self) -> bool {
+        self.open_squares.len() + self.mines.len() == self.all_squares.len()
+    }
+
+    // fn to check if the board's squares win the game, whatever its state
+    // a game is won if all non-mine squares have been dug up (ie. opened),
+    // and every mine has been flagged if flagging is needed to win.
+    // if there is a custom win condition, it replaces this check entirely
+    // (so flagging is only needed to win if the condition asks for it).
+    fn win_reached(&self) -> bool {
+        match &self.win_condition {
+            Some(win_condition) => win_condition(self),
+            None => self.safe_squares_open() && self.flags_placed(),
+        }
+    }
+
+    // fn to determine if the game is won
+    // this is called by open() and flag() whenever a square is opened or flagged,
+    // so callers don't need to call it.
+    pub fn determine_win(&mut self) {
+        // (a game can only be won while it is being played)
+        if self.state == GameState::Playing && self.win_reached() {
+            self.state = GameState::Won;
+        }
+    }
+
+    // fn to validate a move position (used for getting a valid move from the player)
+    // returns the grid index, or none if the player asked to quit
+    fn validate_move_pos(raw: &str, bound: usize) -> Result<Option<usize>, MoveValidationError> {
+        // parse the raw string into a grid index
+        let parse_result = raw.parse::<usize>();
+        // check the parsed result
+        match parse_result {
+            // if parsing was a success and move is within bounds, return the parsed move
+            Ok(parsed_move) if (1..=bound).contains(&parsed_move) => Ok(Some(parsed_move - 1)),
+            // else if there was an error
+            Err(_) => match raw.to_lowercase().as_str() {
+                // if the raw string that we wanted to parse was a quit instruction, signal to quit
+                "q" | "quit" => Ok(None),
+                // else, we do not recognise user's input and return an error
+                _ => Err(MoveValidationError),
+            },
+            // else, error
+            _ => Err(MoveValidationError),
+        }
+    }
+
+    // fn to parse a square named spreadsheet-style, by its col's letters then its row, eg. "AB12"
+    // returns none if cols aren't named with letters, or it isn't a square on the board
+    fn parse_square_name(&self, raw: &str) -> Option<Position> {
+        // guard to check if cols are named with letters
+        if !self.show_letters {
+            return None;
+        }
+        let (raw_x, raw_y) = raw.split_at(raw.find(|c: char| c.is_ascii_digit())?);
+        let x = parse_column_name(raw_x).filter(|&x| x < self.width)?;
+        let y = Self::validate_move_pos(raw_y, self.height).ok()??;
+        Some((x, y))
+    }
+
+    // fn to validate a list of move positions entered on one line, as space-separated x y pairs
+    // (used for getting several moves from the player at once)
+    fn validate_move_list(&self, raw: &str) -> Result<Vec<Position>, MoveValidationError> {
+        let coords: Vec<&str> = raw.split_whitespace().collect();
+        coords
+            .chunks(2)
+            .map(|pair| match pair {
+                [raw_x, raw_y] => match (
+                    Self::validate_move_pos(raw_x, self.width)?,
+                    Self::validate_move_pos(raw_y, self.height)?,
+                ) {
+                    (Some(x), Some(y)) => Ok((x, y)),
+                    _ => Err(MoveValidationError),
+                },
+                // odd number of coords
+                _ => Err(MoveValidationError),
+            })
+            .collect()
+    }
+
+    // fn to get a valid move position from the player
+    // several positions can be entered at once as x y pairs on one line, eg. "3 4 5 6".
+    // returns none if the player asked to quit
+    fn get_move_pos(&self) -> Option<Vec<Position>> {
+        // get raw input of move from the user
+        let named = if self.show_letters {
+            ", a square (eg. A1)"
+        } else {
+            ""
+        };
+        let raw_move_x = get_input(
+            &format!("Enter move x (1-{}){named}, or x y pairs: ", self.width),
+            self.prompts.x.as_deref(),
+            self.quiet,
+        );
+        // if a square was named spreadsheet-style, use it
+        if let Some(pos) = self.parse_square_name(raw_move_x.trim()) {
+            return Some(vec![pos]);
+        }
+        // if several coords were entered, validate them as a list of positions
+        if raw_move_x.trim().contains(char::is_whitespace) {
+            return match self.validate_move_list(raw_move_x.trim()) {
+                Ok(positions) => Some(positions),
+                Err(_) => {
+                    println!("Invalid move.");
+                    self.get_move_pos()
+                }
+            };
+        }
+        // validate the move with bound of board width
+        let move_x = Self::validate_move_pos(raw_move_x.trim(), self.width);
+        // guard to check if player asked to quit
+        if move_x == Ok(None) {
+            return None;
+        }
+
+        // get raw input of move from the user, labelling which way rows are counted
+        let direction = if self.flip_y {
+            "bottom to top"
+        } else {
+            "top to bottom"
+        };
+        let raw_move_y = get_input(
+            &format!("Enter move y (1-{}, {direction}): ", self.height),
+            self.prompts.y.as_deref(),
+            self.quiet,
+        );
+        // validate the move with bound of board height
+        let move_y = Self::validate_move_pos(raw_move_y.trim(), self.height);
+
+        // check if both moves were validated correctly and if so, return them
+        match (move_x, move_y) {
+            (Ok(Some(x)), Ok(Some(y))) => Some(vec![(x, y)]),
+            // player asked to quit
+            (_, Ok(None)) => None,
+            // else we try again
+            _ => {
+                println!("Invalid move.");
+                self.get_move_pos()
+            }
+        }
+    }
+
+    // fn to convert between a displayed row and a board row
+    // this is its own inverse, so it is used in both directions
+    fn display_row(&self, row: usize) -> usize {
+        if self.flip_y {
+            self.height - 1 - row
+        } else {
+            row
+        }
+    }
+
+    // fn to get the range of columns and display rows to display
+    // this is the whole board, unless a viewport is set, in which case it is a window
+    // of the viewport's size centred on the last move (or the middle of the board),
+    // shifted as needed to stay within the board.
+    fn view_ranges(&self) -> (Range<usize>, Range<usize>) {
+        // guard to check if a viewport is set
+        let Some((view_width, view_height)) = self.viewport else {
+            return (0..self.width, 0..self.height);
+        };
+        // get centre of window in display coords
+        let (centre_x, centre_y) = self.last_move.unwrap_or((self.width / 2, self.height / 2));
+        let centre_row = self.display_row(centre_y);
+        // fn to get window of given size around centre, within bound
+        let window = |centre: usize, size: usize, bound: usize| {
+            let start = centre
+                .saturating_sub(size / 2)
+                .min(bound.saturating_sub(size));
+            start..(start + size).min(bound)
+        };
+        (
+            window(centre_x, view_width, self.width),
+            window(centre_row, view_height, self.height),
+        )
+    }
+
+    // fn to get a valid move type from the player
+    fn get_move_type(&self) -> MoveType {
+        // get raw input from player
+        let move_type = get_input(
+            "Enter move type (open/flag/adjacency/resize/clues/satisfied/surrender/explain/weights/show N/nearest/minimap/status/quit, . to repeat): ",
+            self.prompts.move_type.as_deref(),
+            self.quiet,
+        );
+        // check input
+        match move_type.to_lowercase().as_str().trim() {
+            // flag command
+            "f" | "flag" => MoveType::Flag,
+            // open command
+            "o" | "open" => MoveType::Open,
+            // toggle adjacency display command
+            "a" | "adjacency" => MoveType::ToggleAdjacency,
+            // toggle status line display command
+            "status" => MoveType::ToggleStatus,
+            // resize board command
+            "r" | "resize" => MoveType::Resize,
+            // display clues command
+            "c" | "clues" => MoveType::Clues,
+            // show satisfied numbers command
+            "satisfied" => MoveType::Satisfied,
+            // surrender command
+            "s" | "surrender" | "give up" => MoveType::Surrender,
+            // explain solution command
+            "e" | "explain" => MoveType::Explain,
+            // explain weighted number command
+            "w" | "weights" => MoveType::Weights,
+            // quit command
+            "q" | "quit" => MoveType::Quit,
+            // jump to nearest closed square command
+            "n" | "nearest" => MoveType::Nearest,
+            // query true mines near command (debug only)
+            "m" | "mines" => MoveType::MinesNear,
+            // query mines on the frontier and in the interior command (debug only)
+            "frontier" => MoveType::Frontier,
+            // query hash of the board's state command (debug only)
+            "hash" => MoveType::Hash,
+            // display map of how hard the solver finds each square command (debug only)
+            "difficulty" => MoveType::Difficulty,
+            // query distance to nearest mine command (easy mode only)
+            "d" | "distance" => MoveType::Distance,
+            // open random safe squares command (only with helps)
+            "h" | "help" => MoveType::Help,
+            // pause or resume command
+            "p" | "pause" | "resume" => MoveType::Pause,
+            // display minimap command
+            "map" | "minimap" => MoveType::Minimap,
+            // repeat last move type command
+            "." => MoveType::Repeat,
+            other => {
+                let show = other
+                    .strip_prefix("show")
+                    .map(|n| n.trim().parse::<usize>());
+                let variant = other
+                    .strip_prefix("variant")
+                    .map(|v| v.trim().parse::<MinesweeperVariant>());
+                match (show, variant) {
+                    // highlight number command, eg. "show 3"
+                    (Some(Ok(number)), _) => MoveType::Show(number),
+                    // change variant command, eg. "variant knight-paths" (debug only)
+                    (_, Some(Ok(variant))) => MoveType::ChangeVariant(variant),
+                    // invalid - try again
+                    _ => {
+                        println!("Invalid move type.");
+                        self.get_move_type()
+                    }
+                }
+            }
+        }
+    }
+
+    // fn to get new board dimensions from the player (used for resizing)
+    fn get_new_size(&self) -> (usize, usize) {
+        // get raw input of new dimensions from the user
+        let raw_width = get_input("Enter new width: ", None, self.quiet);
+        let raw_height = get_input("Enter new height: ", None, self.quiet);
+        // check if both dimensions were validated correctly and if so, return them
+        if let Ok(size) = Self::validate_size(raw_width.trim(), raw_height.trim()) {
+            size
+        // else we try again
+        } else {
+            println!("Invalid size.");
+            self.get_new_size()
+        }
+    }
+
+    // fn to validate new board dimensions (used for getting a valid size from the player)
+    // both dimensions must be at least 1, and the total number of squares must fit in a usize
+    // so that mine density calculations can't overflow.
+    fn validate_size(raw_width: &str, raw_height: &str) -> Result<Position, SizeValidationError> {
+        // parse both dimensions
+        let (Ok(width), Ok(height)) = (raw_width.parse::<usize>(), raw_height.parse::<usize>())
+        else {
+            return Err(SizeValidationError);
+        };
+        Self::count_squares(width, height).map(|_| (width, height))
+    }
+
+    // fn to get the number of squares on a board of the given size, checking the dimensions are
+    // nonzero and their product doesn't overflow (used to validate sizes from the command line
+    // and from the player)
+    fn count_squares(width: usize, height: usize) -> Result<usize, SizeValidationError> {
+        match width.checked_mul(height) {
+            Some(cells) if cells > 0 => Ok(cells),
+            _ => Err(SizeValidationError),
+        }
+    }
+
+    // fn to check whether a mine is displayed: all mines are once the game is lost,
+    // unless a loss is being animated, in which case only those revealed so far are.
+    // once the game is won, only mines opened while exploring the board are
+    fn mine_revealed(&self, x: usize, y: usize) -> bool {
+        match self.state {
+            GameState::Lost => self
+                .revealed
+                .as_ref()
+                .is_none_or(|revealed| revealed.contains(&(x, y))),
+            GameState::Won => self
+                .revealed
+                .as_ref()
+                .is_some_and(|revealed| revealed.contains(&(x, y))),
+            GameState::Playing | GameState::Quit => false,
+        }
+    }
+
+    // fn to display the board after a loss, revealing mines one by one
+    // mines are revealed in order of distance from the last move, so they spread out from it
+    fn animate_loss(&mut self, delay: Duration) {
+        // sort mines by distance from last move
+        let origin = self.last_move.unwrap_or((0, 0));
+        let mut mines: Vec<Position> = self.mines.iter().copied().collect();
+        mines.sort_by_key(|&(x, y)| (chebyshev_distance((x, y), origin), y, x));
+
+        // reveal mines one by one, displaying the board each time
+        self.revealed = Some(HashSet::new());
+        for mine in mines {
+            if let Some(revealed) = &mut self.revealed {
+                revealed.insert(mine);
+            }
+            self.redraw();
+            thread::sleep(delay);
+        }
+        self.revealed = None;
+    }
+
+    // fn to get what a player can see of a square, for displaying it
+    pub fn square_view(&self, x: usize, y: usize) -> SquareView {
+        // square is outside board's shape
+        if !self.all_squares.contains(&(x, y)) {
+            SquareView::Hole
+        // square is flagged and game not lost (or square is a mine not yet revealed)
+        } else if self.flagged_squares.contains(&(x, y))
+            && (self.state != GameState::Lost
+                || (self.mines.contains(&(x, y)) && !self.mine_revealed(x, y)))
+        {
+            SquareView::Flag
+        // square is a mine, and game is lost (and mine has been revealed)
+        // (otherwise it is unopened, as if square was opened, game would be lost)
+        } else if self.mines.contains(&(x, y)) && self.mine_revealed(x, y) {
+            SquareView::Mine
+        // square is open, but fogged while the game goes on
+        } else if self.fogged.contains(&(x, y)) && self.state == GameState::Playing {
+            SquareView::Fog
+        // square is open, showing the number of mines near
+        } else if self.open_squares.contains(&(x, y)) {
+            SquareView::Open(self.mines_near(x, y))
+        // square is unopened or unflagged
+        } else {
+            SquareView::Closed
+        }
+    }
+
+    // fn to display a single square
+    fn write_square(&self, fmt: &mut fmt::Formatter<'_>, x: usize, y: usize) -> fmt::Result {
+        let view = self.square_view(x, y);
+        // square is the selected square whose neighbors are being highlighted
+        if view != SquareView::Hole && self.hovered == Some((x, y)) {
+            return write!(fmt, "@ ");
+        }
+        // square is a neighbor of the selected square
+        if view != SquareView::Hole
+            && self
+                .hovered
+                .is_some_and(|(hx, hy)| self.neighbors_iter(hx, hy).any(|pos| pos == (x, y)))
+        {
+            return write!(fmt, "* ");
+        }
+        match view {
+            SquareView::Hole => write!(fmt, "  "),
+            SquareView::Closed => write!(fmt, "{}", self.theme.closed),
+            // in detective mode, a correctly flagged mine shows how many mines are near it
+            SquareView::Flag if self.detective && self.mines.contains(&(x, y)) => {
+                write!(fmt, "{}", self.theme.flag_number(self.mines_near(x, y)))
+            }
+            SquareView::Flag => write!(fmt, "{}", self.theme.flag),
+            SquareView::Mine => write!(fmt, "{}", self.theme.mine),
+            SquareView::Fog => write!(fmt, "? "),
+            // display number of mines near if > 0, else opened square
+            SquareView::Open(0) => write!(fmt, "{}", self.theme.open),
+            // if displaying clues, display how many more flags are needed near this square
+            SquareView::Open(mines_value) if self.show_clues => {
+                match mines_value.checked_sub(self.flags_near(x, y)) {
+                    Some(remaining) => write!(fmt, "{remaining} "),
+                    // more flags than mines near this square
+                    None => write!(fmt, "! "),
+                }
+            }
+            // if displaying satisfied numbers, dim those with as many flags near as mines
+            SquareView::Open(mines_value)
+                if self.show_satisfied && self.flags_near(x, y) == mines_value =>
+            {
+                write!(fmt, "{}", self.theme.dimmed(mines_value))
+            }
+            // if highlighting this number, mark it
+            SquareView::Open(mines_value) if self.highlighted == Some(mines_value) => {
+                write!(fmt, "{mines_value}*")
+            }
+            SquareView::Open(mines_value) => write!(fmt, "{}", self.theme.number(mines_value)),
+        }
+    }
+
+    // fn to render the board as an html table, to be styled with css
+    // each square has a class for its state: closed, flag, mine, fog, or open (plus n1-n8 for
+    // the number of mines near, if any), or hole for squares outside the board's shape.
+    // the caption is the number of mines left to flag.
+    pub fn to_html(&self) -> String {
+        let mines_left = self.mines.len().saturating_sub(self.flagged_squares.len());
+        let mut html = format!(
+            "<table class=\"minesweeper\">\n<caption>{mines_left} {}s left</caption>\n",
+            self.theme.mine_word
+        );
+        // for each row (in display order)
+        for row in 0..self.height {
+            let y = self.display_row(row);
+            html += "<tr>";
+            for x in 0..self.width {
+                html += &match self.square_view(x, y) {
+                    SquareView::Hole => "<td class=\"hole\"></td>".to_owned(),
+                    SquareView::Closed => "<td class=\"closed\"></td>".to_owned(),
+                    SquareView::Flag => "<td class=\"flag\"></td>".to_owned(),
+                    SquareView::Mine => "<td class=\"mine\"></td>".to_owned(),
+                    SquareView::Fog => "<td class=\"fog\"></td>".to_owned(),
+                    SquareView::Open(0) => "<td class=\"open\"></td>".to_owned(),
+                    SquareView::Open(number) => {
+                        format!("<td class=\"open n{number}\">{number}</td>")
+                    }
+                };
+            }
+            html += "</tr>\n";
+        }
+        html += "</table>\n";
+        html
+    }
+
+    // fn to play a game of minesweeper
+    fn play(&mut self) {
+        // display board
+        self.redraw();
+        // while we are playing (game not lost or won)
+        while self.state == GameState::Playing {
+            // if spectating, let the solver make every move it can prove first,
+            // stopping if that ends the game
+            if self.spectate && self.paused.is_none() {
+                self.solver_moves();
+                if self.state != GameState::Playing {
+                    break;
+                }
+                // guess one of the safest squares if asked to, rather than asking the player
+                let guess = self
+                    .auto_guess
+                    .and_then(|strategy| self.best_guess(strategy));
+                if let Some(((x, y), chance)) = guess {
+                    self.apply(Action::Open((x, y)));
+                    self.redraw();
+                    println!(
+                        "Solver: guessed ({}, {}), with a {:.0}% chance of being a {}",
+                        x + 1,
+                        y + 1,
+                        chance * 100.0,
+                        self.theme.mine_word
+                    );
+                    continue;
+                }
+                // (before the first open there is nothing to solve yet, so the player just starts)
+                if !self.open_squares.is_empty() {
+                    println!("The solver is stuck, so a guess is needed - your move.");
+                }
+            }
+            // get move pos(es) from player, stopping if they asked to quit
+            // commands other than open and flag only use the first pos
+            let Some(positions) = self.get_move_pos() else {
+                self.apply(Action::Quit);
+                break;
+            };
+            let (x, y) = positions[0];
+            self.last_move = Some((x, y));
+            // if adjacency display is on and square is closed, show its neighbors highlighted
+            if self.show_adjacency && !self.open_squares.contains(&(x, y)) {
+                self.hovered = Some((x, y));
+                self.redraw();
+                self.hovered = None;
+            }
+            // get move type from player, using the last one if asked to repeat it
+            let move_type = match (self.get_move_type(), self.last_move_type) {
+                (MoveType::Repeat, Some(last_move_type)) => last_move_type,
+                (MoveType::Repeat, None) => {
+                    println!("No move to repeat.");
+                    continue;
+                }
+                (move_type, _) => move_type,
+            };
+            self.last_move_type = Some(move_type);
+            // while paused, only resuming (or quitting) is allowed, so the board stays hidden
+            if self.paused.is_some() && !matches!(move_type, MoveType::Pause | MoveType::Quit) {
+                println!("The game is paused - enter pause to resume.");
+                continue;
+            }
+            // once only flagging is left, every closed square is a mine, so don't open one
+            if move_type == MoveType::Open && self.only_flags_left() {
+                println!(
+                    "Every closed square is a {} - flag them instead.",
+                    self.theme.mine_word
+                );
+                continue;
+            }
+            let first_open = self.open_squares.is_empty();
+            // open or flag square based on move type, or run a command
+            match move_type {
+                // open each square in turn, stopping if the game ends
+                MoveType::Open => {
+                    for &(x, y) in &positions {
+                        // tell the player if the square is flagged and opening it does nothing
+                        if self.open_flagged == OpenFlagged::Warn
+                            && self.flagged_squares.contains(&(x, y))
+                        {
+                            println!("({}, {}) is flagged - unflag it first.", x + 1, y + 1);
+                        }
+                        let result = self.apply(Action::Open((x, y)));
+                        // if a life was lost, report the mine hit
+                        if let (ActionResult::MineHit, Some(lives_left), GameState::Playing) =
+                            (result, self.lives, self.state)
+                        {
+                            println!(
+                                "({}, {}) was a {}! Lives left: {lives_left}",
+                                x + 1,
+                                y + 1,
+                                self.theme.mine_word
+                            );
+                        }
+                        // if several squares were entered, report which one was a mine
+                        if self.state == GameState::Lost && positions.len() > 1 {
+                            println!("({}, {}) was a {}!", x + 1, y + 1, self.theme.mine_word);
+                        }
+                        if self.state != GameState::Playing {
+                            break;
+                        }
+                    }
+                }
+                // flag each square in turn, stopping if the game ends
+                MoveType::Flag => {
+                    for &(x, y) in &positions {
+                        self.apply(Action::Flag((x, y)));
+                        if self.state == GameState::Lost {
+                            println!("({}, {}) wasn't a {}!", x + 1, y + 1, self.theme.mine_word);
+                            break;
+                        }
+                    }
+                }
+                MoveType::ToggleAdjacency => self.show_adjacency = !self.show_adjacency,
+                // toggle status line display
+                MoveType::ToggleStatus => self.show_status = !self.show_status,
+                // hide the board until resumed, or show it again
+                MoveType::Pause => self.toggle_pause(),
+                MoveType::Resize => {
+                    let (new_width, new_height) = self.get_new_size();
+                    self.resize(new_width, new_height);
+                }
+                // display the next board with remaining mines needed in place of numbers
+                MoveType::Clues => self.show_clues = true,
+                // list the numbers with as many flags near as mines, and dim them on the next
+                // board, so the player can focus on the rest
+                MoveType::Satisfied => {
+                    self.show_satisfied = true;
+                    let satisfied = self.satisfied_numbers();
+                    if satisfied.is_empty() {
+                        println!("No numbers are satisfied yet.");
+                    } else {
+                        let squares: Vec<String> = satisfied
+                            .iter()
+                            .map(|(x, y)| format!("({}, {})", x + 1, y + 1))
+                            .collect();
+                        println!("Satisfied numbers: {}", squares.join(", "));
+                    }
+                }
+                // lose the game and display the solution instead of the board
+                MoveType::Surrender => {
+                    self.apply(Action::Surrender((x, y)));
+                    println!("You surrendered. Solution:\n{}", self.format_solution());
+                    break;
+                }
+                // display the chain of deductions that solves the board from here
+                MoveType::Explain => self.explain(),
+                // display where the number on this (open) square comes from
+                MoveType::Weights => self.explain_weights(x, y),
+                // display the next board with every open square showing this number marked
+                MoveType::Show(number) => self.highlighted = Some(number),
+                // move to the nearest closed, unflagged square (centring the viewport on it)
+                MoveType::Nearest => match self.nearest_closed_square(x, y) {
+                    Some((new_x, new_y)) => {
+                        println!("Nearest closed square: ({}, {})", new_x + 1, new_y + 1);
+                        self.last_move = Some((new_x, new_y));
+                    }
+                    None => println!("No closed squares left."),
+                },
+                // report the true number of mines near this square, even if closed (debug only)
+                MoveType::MinesNear if self.debug => println!(
+                    "[debug] ({}, {}) has {} {}s near",
+                    x + 1,
+                    y + 1,
+                    self.mines_near(x, y),
+                    self.theme.mine_word
+                ),
+                MoveType::MinesNear => {
+                    println!("The mines command is only available with --debug.")
+                }
+                // report how the mines left are split between the frontier and the interior
+                // (debug only)
+                MoveType::Frontier if self.debug => {
+                    let partition = self.frontier_partition();
+                    println!(
+                        "[debug] frontier: {} {}s in {} squares, interior: {} in {} squares",
+                        partition.frontier_mines,
+                        self.theme.mine_word,
+                        partition.frontier.len(),
+                        partition.interior_mines,
+                        partition.interior.len()
+                    );
+                }
+                MoveType::Frontier => {
+                    println!("The frontier command is only available with --debug.")
+                }
+                // report the hash of the board's state, to spot identical boards (debug only)
+                MoveType::Hash if self.debug => {
+                    println!("[debug] board hash: {:016x}", self.state_hash());
+                }
+                MoveType::Hash => println!("The hash command is only available with --debug."),
+                // display how many rounds of deductions the solver needs for each square
+                // (debug only)
+                MoveType::Difficulty if self.debug => {
+                    println!(
+                        "[debug] rounds of deductions to mark each square (closed if never):\n{}",
+                        self.format_difficulty_map()
+                    );
+                }
+                MoveType::Difficulty => {
+                    println!("The difficulty command is only available with --debug.")
+                }
+                // play the rest of the game with another variant, keeping the mines (debug only)
+                MoveType::ChangeVariant(variant) if self.debug => {
+                    self.change_variant(variant);
+                    println!("[debug] now playing as {}", self.variant_name());
+                }
+                MoveType::ChangeVariant(_) => {
+                    println!("The variant command is only available with --debug.")
+                }
+                // report how far this square is from the nearest mine (easy mode only)
+                MoveType::Distance if self.easy => match self.nearest_mine_distance(x, y) {
+                    Some(0) => println!("({}, {}) is a {}!", x + 1, y + 1, self.theme.mine_word),
+                    Some(distance) => println!(
+                        "The nearest {} is {distance} square{} from ({}, {})",
+                        self.theme.mine_word,
+                        if distance == 1 { "" } else { "s" },
+                        x + 1,
+                        y + 1
+                    ),
+                    None => println!("There are no {}s.", self.theme.mine_word),
+                },
+                MoveType::Distance => {
+                    println!("The distance command is only available with --easy.")
+                }
+                // open a few random safe squares, using up a help
+                MoveType::Help => match self.helps {
+                    Some(helps) if helps > 0 => {
+                        let opened = self.help(&mut SeededRng::from_entropy());
+                        println!(
+                            "Help opened {opened} safe squares. Helps left: {}",
+                            helps - 1
+                        );
+                    }
+                    Some(_) => println!("No helps left."),
+                    None => println!("The help command is only available with --helps."),
+                },
+                // display a downsampled view of the whole board, to find the way around it
+                MoveType::Minimap => println!("{}", self.format_minimap()),
+                // already replaced by the last move type
+                MoveType::Repeat => {}
+                // stop playing
+                MoveType::Quit => {
+                    self.apply(Action::Quit);
+                    break;
+                }
+            };
+            // display board (animating the reveal of mines if the game was just lost)
+            // the board is displayed below explanations rather than redrawn, to keep them visible
+            match (&self.state, self.animate, move_type) {
+                (GameState::Lost, Some(delay), _) => self.animate_loss(delay),
+                (_, _, MoveType::Explain | MoveType::Weights | MoveType::Minimap) => {
+                    println!("{self}")
+                }
+                _ => self.redraw(),
+            }
+            // guide the player through flagging the mines left, if flagging is needed to win
+            if self.only_flags_left() {
+                println!(
+                    "Every safe square is open! Flag the {} {}s left to win.",
+                    self.flag_progress().remaining,
+                    self.theme.mine_word
+                );
+            }
+            // warn of squares no cascade can reach once the first open has set the board
+            // (as it can move mines), so the player knows to look for them
+            if self.warn_isolated && first_open && !self.open_squares.is_empty() {
+                let isolated = self
+                    .unreachable_safe_cells()
+                    .difference(&self.open_squares)
+                    .count();
+                if isolated > 0 {
+                    println!(
+                        "{isolated} safe squares can't be reached by a cascade - open directly."
+                    );
+                }
+            }
+            // tell the player if only guessing is left (once the first open has given them
+            // numbers to go on), if asked to
+            if self.assist
+                && matches!(move_type, MoveType::Open | MoveType::Flag)
+                && self.only_guessing_left()
+            {
+                println!("No safe moves - you'll have to guess.");
+            }
+            // warn the player if they have placed more flags next to numbers than the numbers
+            // allow, if asked to
+            if self.assist && move_type == MoveType::Flag {
+                if let Some((flags, max_mines)) = self.over_flagged() {
+                    println!(
+                        "{flags} flags are next to numbers, which allow at most {max_mines} {}s \
+                        - check them for mistakes.",
+                        self.theme.mine_word
+                    );
+                }
+            }
+            // clues, satisfied numbers and highlights are only displayed once
+            self.show_clues = false;
+            self.show_satisfied = false;
+            self.highlighted = None;
+            // save game in case of a crash
+            self.autosave();
+        }
+        // guard to check if the player quit, leaving the game (and its autosave) as it is
+        if self.state == GameState::Quit {
+            if !self.quiet {
+                println!("Quitting...");
+            }
+            return;
+        }
+        // game is over, so there is nothing left to resume
+        if let Some(path) = &self.autosave {
+            // ignore err as autosave may never have been written
+            let _ = std::fs::remove_file(path);
+        }
+        // write replay if asked to
+        if let Some(path) = &self.record {
+            if let Err(err) = std::fs::write(path, self.format_replay()) {
+                println!("Warning: unable to write replay to {path}: {err}");
+            }
+        }
+        // write move history if asked to
+        if let Some(path) = &self.history {
+            if let Err(err) = std::fs::write(path, self.export_history()) {
+                println!("Warning: unable to write move history to {path}: {err}");
+            }
+        }
+        // write final board as html if asked to
+        if let Some(path) = &self.html {
+            if let Err(err) = std::fs::write(path, self.to_html()) {
+                println!("Warning: unable to write html to {path}: {err}");
+            }
+        }
+        // write board as a .mbf file if asked to
+        if let Some(path) = &self.mbf {
+            match self.to_mbf() {
+                Ok(mbf) => {
+                    if let Err(err) = std::fs::write(path, mbf) {
+                        println!("Warning: unable to write .mbf file to {path}: {err}");
+                    }
+                }
+                Err(err) => println!("Warning: unable to write .mbf file to {path}: {err}"),
+            }
+        }
+        // display result
+        match self.state {
+            GameState::Won => println!("You won!"),
+            GameState::Lost => println!("You lost!"),
+            GameState::Playing | GameState::Quit => {}
+        }
+        // display the board played, how fragmented the cleared area ended up,
+        // and the most opened at once (unless quiet)
+        if !self.quiet {
+            println!(
+                "Board: {}x{} {} with {} {}s",
+                self.width(),
+                self.height(),
+                self.variant_name(),
+                self.mine_count(),
+                self.theme.mine_word
+            );
+            println!("Open regions: {}", self.open_region_count());
+            println!("Longest cascade: {} squares", self.longest_cascade);
+            println!(
+                "Squares opened: {} directly, {} by cascades",
+                self.direct_opens, self.cascade_opens
+            );
+            println!("Mine symmetry: {}", self.mine_symmetry());
+        }
+        // display mine clusters if asked to
+        if self.show_clusters {
+            println!("{}", self.format_clusters());
+        }
+        // display mine probabilities at each guess if asked to
+        if self.show_guesses {
+            for (i, (board, pos)) in self.guesses.iter().enumerate() {
+                println!("Guess {}: {}", i + 1, board.format_guess(*pos));
+            }
+        }
+        // record the win on the leaderboard if asked to
+        if let (GameState::Won, Some(path)) = (self.state, &self.leaderboard) {
+            self.record_score(path);
+        }
+        // let the player explore the board if they won and asked to
+        if self.explore && self.state == GameState::Won {
+            self.explore_board();
+        }
+    }
+
+    // fn to let the player open and flag the squares left after winning, until they quit
+    // (moves aren't recorded, as the game is already over). these are the mines, and any safe
+    // squares a custom win condition didn't need opened
+    fn explore_board(&mut self) {
+        println!(
+            "Exploring the board: open or flag the squares left, without losing to a {}.",
+            self.theme.mine_word
+        );
+        loop {
+            let Some(positions) = self.get_move_pos() else {
+                return;
+            };
+            match self.get_move_type() {
+                MoveType::Open => {
+                    for (x, y) in positions {
+                        // ignore err as squares outside the board's shape just do nothing
+                        let _ = self.open(x, y);
+                    }
+                }
+                MoveType::Flag => {
+                    for (x, y) in positions {
+                        let _ = self.flag(x, y);
+                    }
+                }
+                MoveType::Quit => return,
+                _ => {
+                    println!("Only open, flag and quit are available while exploring.");
+                    continue;
+                }
+            }
+            self.redraw();
+        }
+    }
+
+    // fn to record the board before opening a square, if opening it is a guess
+    // (the square isn't certain to be safe from what the player can see).
+    // the first open is never a guess, as nothing can be seen.
+    fn record_guess(&mut self, x: usize, y: usize) {
+        // guard to check if square can be opened and there is something to go on
+        if self.open_squares.is_empty()
+            || self.open_squares.contains(&(x, y))
+            || self.flagged_squares.contains(&(x, y))
+        {
+            return;
+        }
+        // record board if square may be a mine
+        if self
+            .mine_probabilities()
+            .get(&(x, y))
+            .is_some_and(|&p| p > 0.0)
+        {
+            let mut board = self.clone();
+            board.guesses.clear();
+            self.guesses.push((board, (x, y)));
+        }
+    }
+
+    // fn to open a square as the player's move, returning the number of squares opened
+    // (including fogged squares opened directly, which were already in the open squares)
+    fn open_move(&mut self, x: usize, y: usize) -> usize {
+        // if reviewing guesses, record the board if this open is a guess
+        if self.show_guesses {
+            self.record_guess(x, y);
+        }
+        // keep track of the most squares opened at once
+        // (squares outside the board's shape can be entered, but open nothing)
+        let lives = self.lives;
+        let opened = self.open(x, y).unwrap_or(0);
+        self.longest_cascade = self.longest_cascade.max(opened);
+        // keep track of squares opened directly and by cascades
+        if opened > 0 {
+            self.direct_opens += 1;
+            self.cascade_opens += opened - 1;
+        }
+        // record move for replays, unless it did nothing
+        if opened > 0 || self.state == GameState::Lost || self.lives != lives {
+            self.record_move(MoveType::Open, x, y);
+        }
+        opened
+    }
+
+    // fn to record a move made for replays and the move history, with when it was made
+    fn record_move(&mut self, move_type: MoveType, x: usize, y: usize) {
+        self.moves.push((move_type, (x, y)));
+        self.move_times.push(self.elapsed());
+    }
+
+    // fn to get the time taken since the first open (stopped while paused if pausing stops
+    // the timer)
+    fn elapsed(&self) -> Duration {
+        self.started
+            .map_or(Duration::ZERO, |started| match self.paused {
+                Some(paused) if self.pause_stops_timer => paused.duration_since(started),
+                _ => started.elapsed(),
+            })
+    }
+
+    // fn to pause the game, hiding the board, or resume it
+    // if pausing stops the timer, the start time is moved on by the time spent paused
+    fn toggle_pause(&mut self) {
+        match self.paused.take() {
+            Some(paused) if self.pause_stops_timer => {
+                self.started = self.started.map(|started| started + paused.elapsed());
+            }
+            Some(_) => {}
+            None => self.paused = Some(Instant::now()),
+        }
+    }
+
+    // fn to give up the game as the player's move (recording the square it was given up on)
+    fn surrender(&mut self, x: usize, y: usize) {
+        self.state = GameState::Lost;
+        self.record_move(MoveType::Surrender, x, y);
+    }
+
+    // fn to step the game by an action as the player's move, returning what it did
+    // opens, flags and surrenders are recorded for replays (unless they do nothing), and
+    // squares off the board do nothing. once the game is over, actions do nothing.
+    pub fn apply(&mut self, action: Action) -> ActionResult {
+        // guard to check if the game has ended
+        if self.state != GameState::Playing {
+            return ActionResult::Nothing;
+        }
+        match action {
+            Action::Open((x, y)) => {
+                let lives = self.lives;
+                let opened = self.open_move(x, y);
+                if self.state == GameState::Lost || self.lives != lives {
+                    ActionResult::MineHit
+                } else if opened > 0 {
+                    ActionResult::Opened(opened)
+                } else {
+                    ActionResult::Nothing
+                }
+            }
+            Action::Flag((x, y)) => {
+                let flagged = self.flagged_squares.contains(&(x, y));
+                self.flag_move(x, y);
+                match (flagged, self.flagged_squares.contains(&(x, y))) {
+                    (false, true) => ActionResult::Flagged,
+                    (true, false) => ActionResult::Unflagged,
+                    _ => ActionResult::Nothing,
+                }
+            }
+            Action::Surrender((x, y)) => {
+                self.surrender(x, y);
+                ActionResult::Ended
+            }
+            Action::Quit => {
+                self.state = GameState::Quit;
+                ActionResult::Ended
+            }
+        }
+    }
+
+    // fn to apply a list of moves to the game without any input or output (eg. for benchmarks),
+    // returning the state the game ends in. opens, flags and surrenders have the same effect as
+    // in play (including being recorded for replays), and moves off the board do nothing.
+    // moves that only change the display, and moves after the game ends, are ignored.
+    pub fn play_moves(&mut self, moves: &[Move]) -> GameState {
+        for action in moves
+            .iter()
+            .filter_map(|&pos_move| Action::from_move(pos_move))
+        {
+            self.apply(action);
+        }
+        self.state
+    }
+
+    // fn to flag a square as the player's move
+    fn flag_move(&mut self, x: usize, y: usize) {
+        // record move for replays, unless it did nothing
+        // (ignore err as squares outside the board's shape just can't be flagged)
+        let num_flags = self.flagged_squares.len();
+        let _ = self.flag(x, y);
+        if self.flagged_squares.len() != num_flags {
+            self.record_move(MoveType::Flag, x, y);
+        }
+    }
+
+    // fn to find how far a square is from the nearest mine (None if there are no mines)
+    // distance is chebyshev distance, so a square with a mine near is 1 from it
+    // whatever the variant, as this doesn't use adjacency
+    fn nearest_mine_distance(&self, x: usize, y: usize) -> Option<usize> {
+        self.mines
+            .iter()
+            .map(|&mine| chebyshev_distance(mine, (x, y)))
+            .min()
+    }
+
+    // fn to find the closed, unflagged square nearest to (but not at) a square
+    // distance is chebyshev distance, with ties broken by reading order
+    fn nearest_closed_square(&self, x: usize, y: usize) -> Option<Position> {
+        self.closed_cells()
+            .filter(|&pos| pos != (x, y))
+            .min_by_key(|&(nx, ny)| (chebyshev_distance((nx, ny), (x, y)), ny, nx))
+    }
+
+    // fn to display the board, clearing the screen first to redraw it in place if asked to,
+    // followed by any notices from the game since it was last displayed
+    fn redraw(&mut self) {
+        if self.redraw {
+            // move cursor to top left and clear screen
+            print!("\x1b[H\x1b[2J");
+        }
+        println!("{self}");
+        self.print_notices();
+    }
+
+    // fn to display the notices from the game since they were last displayed
+    // (the game itself doesn't print, so that it can be played without a terminal)
+    fn print_notices(&mut self) {
+        for notice in self.notices.drain(..) {
+            println!("{notice}");
+        }
+    }
+
+    // fn to check if the game is still going, but no closed square can be proven safe or a mine
+    // (so the next move is a guess). this is never the case before the first open, as every
+    // move is a guess then, or once only flagging is left
+    fn only_guessing_left(&self) -> bool {
+        self.state == GameState::Playing
+            && !self.open_squares.is_empty()
+            && !self.only_flags_left()
+            && !self.has_certain_move()
+    }
+
+    // fn to check if more flags are on the frontier (next to an open number) than the numbers
+    // there allow mines, so some of them must be wrong. returns the number of flags on the
+    // frontier and the most mines it can hold, if there are too many
+    fn over_flagged(&self) -> Option<(usize, usize)> {
+        let frontier = self.frontier_partition().frontier;
+        let flags = self.flagged_squares.intersection(&frontier).count();
+        let max_mines = self.max_frontier_mines();
+        (flags > max_mines).then_some((flags, max_mines))
+    }
+
+    // fn to display the chain of deductions that solves the board from its current state
+    fn explain(&self) {
+        // guard to check if any deductions can be made
+        if !self.has_certain_move() {
+            println!("No deductions can be made from here - you'll have to guess.");
+            return;
+        }
+        // display what can be proven from the numbers shown now, before any more are opened
+        let (safe, mines) = self.determinable_cells(Technique::Subset);
+        println!(
+            "Provable now: {} safe squares and {} mines.",
+            safe.len(),
+            mines.len()
+        );
+        let steps = self.solution_steps();
+        // display each step
+        println!("Deductions:");
+        for (i, step) in steps.iter().enumerate() {
+            println!("{}. {step}", i + 1);
+        }
+    }
+
+    // fn to let the solver play: open every square it can prove safe and flag every square it
+    // can prove to be a mine, displaying the board and the deduction after each step.
+    // stops when the game ends or a guess is needed.
+    fn solver_moves(&mut self) {
+        let mut known_mines = HashSet::<Position>::new();
+        while self.state == GameState::Playing {
+            // find next deduction, stopping if there isn't one
+            let Some(step) = self.next_deduction(&known_mines, &HashSet::new(), Technique::Subset)
+            else {
+                return;
+            };
+            // flag mines not already flagged, and open safe squares
+            // (removing any wrong flags in the way)
+            let mut changed = false;
+            for &(x, y) in &step.mines {
+                if !self.flagged_squares.contains(&(x, y)) {
+                    self.apply(Action::Flag((x, y)));
+                    changed = true;
+                }
+            }
+            for &(x, y) in &step.safe {
+                self.flagged_squares.remove(&(x, y));
+                self.apply(Action::Open((x, y)));
+                changed = true;
+            }
+            known_mines.extend(step.mines.iter().copied());
+            // display step, unless it only found mines already flagged
+            if changed {
+                self.redraw();
+                println!("Solver: {step}");
+            }
+        }
+    }
+
+    // fn to get the number of mines orthogonally and diagonally adjacent to a square
+    // used to break down the count on a doubled variant square, where orthogonal mines are
+    // counted twice (so the displayed number is 2 * orthogonal + diagonal)
+    fn mines_near_by_direction(&self, x: usize, y: usize) -> (usize, usize) {
+        // fn to count mines at the given offsets from the square
+        let count_mines = |offsets: [(isize, isize); 4]| {
+            offsets
+                .into_iter()
+                .filter_map(|(dx, dy)| Some((x.checked_add_signed(dx)?, y.checked_add_signed(dy)?)))
+                .filter(|pos| self.mines.contains(pos))
+                .count()
+        };
+        (
+            count_mines([(-1, 0), (1, 0), (0, -1), (0, 1)]),
+            count_mines([(-1, -1), (1, 1), (-1, 1), (1, -1)]),
+        )
+    }
+
+    // fn to display the breakdown of the number on an open doubled variant square
+    fn explain_weights(&self, x: usize, y: usize) {
+        // guard to check the variant weights squares
+        if self.variant_at(x) != MinesweeperVariant::Doubled {
+            println!("Only the doubled variant counts some squares more than once.");
+            return;
+        }
+        // guard to check square is open (otherwise this would reveal mines)
+        if !self.open_squares.contains(&(x, y)) {
+            println!("Only open squares can be broken down.");
+            return;
+        }
+        let (orthogonal, diagonal) = self.mines_near_by_direction(x, y);
+        println!(
+            "{} = {orthogonal} orthogonal mines (counted twice) + {diagonal} diagonal mines",
+            self.mines_near(x, y)
+        );
+    }
+
+    // fn to format a replay of the game so far, to verify it later
+    // the replay format is one field per line: the seed the board was generated from,
+    // the game's outcome, and the moves made as space-separated eg. "open x,y" or "flag x,y".
+    // the board size, mines and variant aren't saved, so must be given again to verify it.
+    fn format_replay(&self) -> String {
+        let moves: String = self
+            .moves
+            .iter()
+            .map(|&(move_type, (x, y))| format!(" {} {x},{y}", recorded_move_name(move_type)))
+            .collect();
+        format!(
+            "seed {}\noutcome {}\nmoves{moves}\n",
+            self.seed.unwrap_or(0),
+            self.state.name()
+        )
+    }
+
+    // fn to export the history of moves made, for analysing play with other tools: one line
+    // per move, of the milliseconds since the first open it was made at, its type and square
+    // (0-based), eg. "1520 flag 3,4"
+    pub fn export_history(&self) -> String {
+        self.moves
+            .iter()
+            .zip(&self.move_times)
+            .map(|(&(move_type, (x, y)), time)| {
+                let name = recorded_move_name(move_type);
+                format!("{} {name} {x},{y}\n", time.as_millis())
+            })
+            .collect()
+    }
+
+    // fn to parse a replay into its seed, moves and claimed outcome
+    fn parse_replay(replay: &str) -> Result<(u64, Vec<Move>, GameState), LoadError> {
+        // map each field name to its value
+        let fields: HashMap<&str, &str> = replay
+            .lines()
+            .map(|line| line.split_once(' ').unwrap_or((line, "")))
+            .collect();
+        // fn to get a field's value, erring if missing
+        let field = |name: &str| fields.get(name).copied().ok_or(LoadError::Malformed);
+
+        // parse moves, which come in pairs of move type and position
+        let tokens: Vec<&str> = field("moves")?.split_whitespace().collect();
+        let moves = tokens
+            .chunks(2)
+            .map(|chunk| {
+                let [raw_type, raw_pos] = chunk else {
+                    return Err(LoadError::Malformed);
+                };
+                let move_type = match *raw_type {
+                    "open" => MoveType::Open,
+                    "flag" => MoveType::Flag,
+                    "surrender" => MoveType::Surrender,
+                    _ => return Err(LoadError::Malformed),
+                };
+                let (raw_x, raw_y) = raw_pos.split_once(',').ok_or(LoadError::Malformed)?;
+                match (raw_x.parse::<usize>(), raw_y.parse::<usize>()) {
+                    (Ok(x), Ok(y)) => Ok((move_type, (x, y))),
+                    _ => Err(LoadError::Malformed),
+                }
+            })
+            .collect::<Result<Vec<Move>, LoadError>>()?;
+        let seed = field("seed")?.parse().map_err(|_| LoadError::Malformed)?;
+        Ok((seed, moves, field("outcome")?.parse()?))
+    }
+
+    // fn to reconstruct a recorded game as it was after its first n moves (or all of them, if
+    // there are fewer), so it can be played on from there. the moves kept are recorded again,
+    // so a replay of the continued game starts the same way
+    pub fn replay_to(settings: GameSettings, seed: u64, moves: &[Move], n: usize) -> Self {
+        let mut game = Self::new(GameSettings {
+            seed: Some(seed),
+            ..settings
+        });
+        game.play_moves(&moves[..n.min(moves.len())]);
+        game
+    }
+
+    // fn to check that a recorded game is genuine: replaying its moves on the board
+    // generated from its seed must end in the outcome claimed.
+    // any impossible move (off the board, after the game ended, opening an open or flagged square,
+    // or flagging an open square) means the replay is not genuine.
+    // replays of games which were resized can't be verified, as resizing generates a new board
+    pub fn verify_replay(
+        settings: GameSettings,
+        seed: u64,
+        moves: &[Move],
+        claimed_outcome: &GameState,
+    ) -> bool {
+        // generate board from seed
+        let mut game = Self::new(GameSettings {
+            seed: Some(seed),
+            ..settings
+        });
+        // replay each move, checking it is possible
+        for &(move_type, (x, y)) in moves {
+            if game.state != GameState::Playing {
+                return false;
+            }
+            let is_open = game.open_squares.contains(&(x, y));
+            let is_possible = match move_type {
+                MoveType::Open => !is_open && !game.flagged_squares.contains(&(x, y)),
+                MoveType::Flag => !is_open,
+                MoveType::Surrender => true,
+                _ => false,
+            };
+            // moves off the board are impossible too
+            if !is_possible || !game.all_squares.contains(&(x, y)) {
+                return false;
+            }
+            game.play_moves(&[(move_type, (x, y))]);
+        }
+        // check outcome
+        game.state == *claimed_outcome
+    }
+
+    // fn to simulate naive random play on this board
+    // each game starts from this board's current state (after opening the given square, if any)
+    // and opens random closed, unflagged squares until it is won or lost, recording how many
+    // opens ("clicks") it took. squares are picked with an rng from the given seed, which also
+    // places any mines moved by the first open if the game has no seed of its own, so the same
+    // seed always gives the same stats. the opening is only made once, so mines moved away from
+    // it (eg. by a safe radius) are in the same place in every game.
+    fn simulate(&self, num_games: usize, opening: Option<Position>, seed: u64) -> SimulationStats {
+        let mut stats = SimulationStats {
+            games: num_games,
+            seed,
+            wins: 0,
+            clicks_to_loss: Vec::new(),
+        };
+        // make the opening on the board every game starts from
+        let mut start = self.clone();
+        start.seed = Some(self.seed.unwrap_or(seed));
+        if let Some((x, y)) = opening {
+            // ignore err as the opening is checked to be on the board
+            let _ = start.open(x, y);
+        }
+        let mut rng = SeededRng::new(seed);
+        for _ in 0..num_games {
+            // play on a copy of the board
+            let mut game = start.clone();
+            let mut clicks = 0;
+            while game.state == GameState::Playing {
+                // pick a random closed, unflagged square and open it
+                // (sorted so that the square picked only depends on the rng)
+                let mut closed: Vec<Position> = game
+                    .all_squares
+                    .iter()
+                    .filter(|pos| !game.open_squares.contains(pos))
+                    .filter(|pos| !game.flagged_squares.contains(pos))
+                    .copied()
+                    .collect();
+                closed.sort_unstable();
+                // guard to check there is something left to open (only not the case if flags
+                // cover every remaining square, which random play can't undo)
+                if closed.is_empty() {
+                    break;
+                }
+                let (x, y) = closed[rng.gen_index(closed.len())];
+                // ignore err as closed squares are on the board
+                let _ = game.open(x, y);
+                clicks += 1;
+            }
+            // record result
+            match game.state {
+                GameState::Won => stats.wins += 1,
+                GameState::Lost => stats.clicks_to_loss.push(clicks),
+                GameState::Playing | GameState::Quit => {}
+            }
+        }
+        // return stats
+        stats
+    }
+
+    // fn to detect which symmetries the mine layout has, relative to the board's rectangle
+    pub fn mine_symmetry(&self) -> Symmetry {
+        // fn to check if mapping every mine gives another mine
+        let symmetric = |map: fn(&Self, Position) -> Position| {
+            self.mines
+                .iter()
+                .all(|&mine| self.mines.contains(&map(self, mine)))
+        };
+        Symmetry {
+            horizontal: symmetric(|game, (x, y)| (x, game.height - 1 - y)),
+            vertical: symmetric(|game, (x, y)| (game.width - 1 - x, y)),
+            rotational: symmetric(|game, (x, y)| (game.width - 1 - x, game.height - 1 - y)),
+        }
+    }
+
+    // fn to hash the board's whole state (its size, variant, shape, mines, and open and flagged
+    // squares), so that tools can find identical boards (eg. to cache analyses of them).
+    // squares are hashed one at a time and combined with xor, so the hash doesn't depend on the
+    // order sets are iterated in, and values are mixed with the game's own rng (rather than
+    // std's hasher), so the hash stays the same between runs and builds
+    pub fn state_hash(&self) -> u64 {
+        // fn to mix a value into a hash
+        let combine = |hash: u64, value: u64| SeededRng::new(hash ^ value).next_u64();
+        // fn to hash a set of squares, starting from a different tag for each set so squares
+        // in different sets don't cancel out
+        let hash_squares = |tag: u64, squares: &HashSet<Position>| {
+            squares.iter().fold(0, |hash, &(x, y)| {
+                hash ^ [x as u64, y as u64].into_iter().fold(tag, combine)
+            })
+        };
+        let variant = self.variant_name().bytes().map(u64::from).fold(0, combine);
+        [
+            self.width as u64,
+            self.height as u64,
+            variant,
+            hash_squares(1, &self.all_squares),
+            hash_squares(2, &self.mines),
+            hash_squares(3, &self.open_squares),
+            hash_squares(4, &self.flagged_squares),
+        ]
+        .into_iter()
+        .fold(0, combine)
+    }
+
+    // fn to group mines into clusters of mines which are neighbors of each other
+    // (as specified by the game's variant), in reading order of their first mine
+    fn mine_clusters(&self) -> Vec<HashSet<Position>> {
+        self.connected_regions(&self.mines)
+    }
+
+    // fn to count the connected regions of open squares (how fragmented the cleared area is)
+    pub fn open_region_count(&self) -> usize {
+        self.connected_regions(&self.open_squares).len()
+    }
+
+    // fn to count the 3bv of the board: the fewest clicks needed to open every safe square
+    // (without flagging). that is one click for each region of squares with no mines near,
+    // which cascades open, and one for each other safe square no cascade can reach
+    fn three_bv(&self) -> usize {
+        let zeros: HashSet<Position> = self
+            .all_squares
+            .iter()
+            .filter(|&&(x, y)| !self.mines.contains(&(x, y)) && self.mines_near(x, y) == 0)
+            .copied()
+            .collect();
+        let unreachable = self.unreachable_safe_cells();
+        self.connected_regions(&zeros).len() + unreachable.difference(&zeros).count()
+    }
+
+    // fn to score a won game, for the leaderboard: its 3bv per second, times its efficiency
+    // (3bv per move made), times its difficulty (mines per square), scaled up to whole points.
+    // a game won in under a second counts as taking one
+    fn score(&self) -> usize {
+        let bv = self.three_bv() as f64;
+        let seconds = self.elapsed().as_secs_f64().max(1.0);
+        let efficiency = bv / self.moves.len().max(1) as f64;
+        let difficulty = self.mines.len() as f64 / self.all_squares.len().max(1) as f64;
+        (bv / seconds * efficiency * difficulty * 10000.0).round() as usize
+    }
+
+    // fn to describe the board settings for the leaderboard, eg. "9x9 normal 10"
+    // (scores are only compared between games with the same description)
+    fn leaderboard_board(&self) -> String {
+        format!(
+            "{}x{} {} {}",
+            self.width,
+            self.height,
+            self.variant_name(),
+            self.mines.len()
+        )
+    }
+
+    // fn to find the settings which made the game easier to win, if any
+    fn assistance(&self) -> Vec<&'static str> {
+        [
+            (self.lives.is_some(), "lives"),
+            (self.helps.is_some(), "helps"),
+            (self.pre_opened, "pre-opened squares"),
+            (self.assist, "assist"),
+            (self.no_guessing, "no guessing"),
+            (self.safe_radius.is_some(), "a safe radius"),
+        ]
+        .into_iter()
+        .filter_map(|(assisted, name)| assisted.then_some(name))
+        .collect()
+    }
+
+    // fn to record a won game on the leaderboard, then display its top scores
+    // (games the solver played, or played with assistance, aren't recorded)
+    fn record_score(&self, path: &str) {
+        let board = self.leaderboard_board();
+        let assistance = self.assistance();
+        if self.spectate {
+            println!("Not recorded on the leaderboard, as the solver played.");
+        } else if !assistance.is_empty() {
+            println!(
+                "Not recorded on the leaderboard, as it was played with {}.",
+                assistance.join(", ")
+            );
+        } else {
+            let score = Score {
+                points: self.score(),
+                board: board.clone(),
+                name: self.player.clone(),
+            };
+            println!("Score: {} points", score.points);
+            if let Err(err) = leaderboard::record(path, &score) {
+                println!("Warning: unable to write leaderboard to {path}: {err}");
+            }
+        }
+        match leaderboard::load(path) {
+            Ok(scores) => println!("{}", leaderboard::format_top(&scores, &board)),
+            Err(err) => println!("Warning: unable to read leaderboard from {path}: {err}"),
+        }
+    }
+
+    // fn to find the safe squares which no cascade can ever open, as they aren't a neighbor of
+    // any other safe square with no mines near. these can only be opened directly.
+    // this is common in variants with few neighbors, like diagonal
+    pub fn unreachable_safe_cells(&self) -> HashSet<Position> {
+        // find every square a cascade can spread to: the neighbors of safe zeros
+        let reachable: HashSet<Position> = self
+            .all_squares
+            .iter()
+            .filter(|pos| !self.mines.contains(pos))
+            .filter(|&&(x, y)| self.mines_near(x, y) == 0)
+            .flat_map(|&(x, y)| self.neighbors_iter(x, y).filter(move |&pos| pos != (x, y)))
+            .collect();
+        self.all_squares
+            .iter()
+            .filter(|pos| !self.mines.contains(pos) && !reachable.contains(pos))
+            .copied()
+            .collect()
+    }
+
+    // fn to group the given squares into regions of squares which are neighbors of each other
+    // (as specified by the game's variant). as some variants aren't symmetric,
+    // two squares are connected if either one is a neighbor of the other.
+    // regions are returned in reading order of their first square.
+    fn connected_regions(&self, squares: &HashSet<Position>) -> Vec<HashSet<Position>> {
+        // build map from each square to its connected squares
+        let mut connections = HashMap::<Position, Vec<Position>>::new();
+        for &(x, y) in squares {
+            for neighbor in self.neighbors_iter(x, y) {
+                // only connect to other given squares
+                if neighbor == (x, y) || !squares.contains(&neighbor) {
+                    continue;
+                }
+                // connect in both directions
+                connections.entry((x, y)).or_default().push(neighbor);
+                connections.entry(neighbor).or_default().push((x, y));
+            }
+        }
+
+        // sort squares into reading order (row by row)
+        let mut squares: Vec<Position> = squares.iter().copied().collect();
+        squares.sort_by_key(|&(x, y)| (y, x));
+
+        // breadth first search from each square not yet in a region
+        let mut regions = Vec::<HashSet<Position>>::new();
+        let mut visited = HashSet::<Position>::new();
+        for square in squares {
+            // guard to check if square is already in a region
+            if !visited.insert(square) {
+                continue;
+            }
+            // collect all squares connected to this one
+            let mut region = HashSet::from([square]);
+            let mut queue = VecDeque::from([square]);
+            while let Some(current) = queue.pop_front() {
+                for &next in connections.get(&current).into_iter().flatten() {
+                    if visited.insert(next) {
+                        region.insert(next);
+                        queue.push_back(next);
+                    }
+                }
+            }
+            regions.push(region);
+        }
+        // return regions
+        regions
+    }
+
+    // fn to format the full solution: every mine and the number of mines near every other square
+    fn format_solution(&self) -> String {
+        self.format_board(|x, y| {
+            if self.mines.contains(&(x, y)) {
+                self.theme.mine.to_owned()
+            } else {
+                self.format_number(x, y)
+            }
+        })
+    }
+
+    // fn to format a heatmap of how hard the solver finds each square: the number of rounds of
+    // deductions it takes to mark it (colored like numbers, and + for more than 9). open
+    // squares are blank, and squares the solver can't mark without guessing stay closed
+    fn format_difficulty_map(&self) -> String {
+        let rounds = self.difficulty_map();
+        self.format_board(|x, y| match rounds.get(&(x, y)) {
+            _ if self.open_squares.contains(&(x, y)) => self.theme.open.to_owned(),
+            Some(&round) if round <= 9 => self.theme.number(round),
+            Some(_) => "+ ".to_owned(),
+            None => self.theme.closed.to_owned(),
+        })
+    }
+
+    // fn to format the number of mines near a square (blank if none) in the game's theme
+    fn format_number(&self, x: usize, y: usize) -> String {
+        match self.mines_near(x, y) {
+            0 => self.theme.open.to_owned(),
+            mines_value => self.theme.number(mines_value),
+        }
+    }
+
+    // fn to format the full solution with each mine labelled by its cluster (A-Z, repeating)
+    fn format_clusters(&self) -> String {
+        // map each mine to its cluster label
+        let clusters = self.mine_clusters();
+        let mut labels = HashMap::<Position, char>::new();
+        for (i, cluster) in clusters.iter().enumerate() {
+            let label = (b'A' + (i % 26) as u8) as char;
+            labels.extend(cluster.iter().map(|&mine| (mine, label)));
+        }
+
+        // display label for mines, number of mines near for other squares
+        let board = self.format_board(|x, y| match labels.get(&(x, y)) {
+            Some(label) => format!("{label} "),
+            None => self.format_number(x, y),
+        });
+        format!("{} mine clusters:\n{board}", clusters.len())
+    }
+
+    // fn to format the mine probabilities before a guess as a heatmap, with the guessed square
+    // marked by @. closed squares show the tens digit of their percentage chance of being a mine
+    // (or # if certainly a mine, - if certainly safe), colored from green to red
+    // when displaying to a terminal.
+    fn format_guess(&self, guess: Position) -> String {
+        let probabilities = self.mine_probabilities();
+        let percent = |pos: &Position| probabilities.get(pos).map_or(0.0, |p| p * 100.0);
+        // find safest closed square, to compare the guess with
+        let safest = probabilities.values().fold(1.0, |a: f64, &b| a.min(b)) * 100.0;
+        let colored = stdout().is_terminal();
+
+        // display number for open squares, percentage for closed squares
+        let board = self.format_board(|x, y| {
+            if self.open_squares.contains(&(x, y)) {
+                return self.format_number(x, y);
+            }
+            let percent = percent(&(x, y));
+            let square = match ((x, y) == guess, percent) {
+                (true, _) => "@ ".to_owned(),
+                (false, p) if p >= 100.0 => "# ".to_owned(),
+                (false, p) if p <= 0.0 => "- ".to_owned(),
+                (false, p) => format!("{}%", (p / 10.0) as usize),
+            };
+            // color green below 1/3, yellow below 2/3, red above
+            match (colored, percent) {
+                (false, _) => square,
+                (true, p) if p < 100.0 / 3.0 => format!("\x1b[32m{square}\x1b[0m"),
+                (true, p) if p < 200.0 / 3.0 => format!("\x1b[33m{square}\x1b[0m"),
+                (true, _) => format!("\x1b[31m{square}\x1b[0m"),
+            }
+        });
+        format!(
+            "({}, {}) had a {:.0}% chance of being a mine (safest square: {safest:.0}%)\n{board}",
+            guess.0 + 1,
+            guess.1 + 1,
+            percent(&guess)
+        )
+    }
+
+    // fn to format the mine count of each of the given cols, to display below the board
+    // (counts of more than one digit are written vertically, so each stays under its col)
+    fn format_col_ruler(&self, cols: Range<usize>) -> String {
+        // get mine count of each col
+        let counts: Vec<String> = cols
+            .map(|x| {
+                self.mines
+                    .iter()
+                    .filter(|&&(mx, _)| mx == x)
+                    .count()
+                    .to_string()
+            })
+            .collect();
+        format_col_labels(&counts)
+    }
+
+    // fn to format the board with a border, using a function/closure to format each square
+    // (used for alternate displays of the board - each square must take up 2 chars)
+    fn format_board(&self, format_square: impl Fn(usize, usize) -> String) -> String {
+        // generate horizontal border (see Display impl)
+        let horiz_border = "+".to_owned() + &"-".repeat(self.width * 2 + 1) + "+\n";
+
+        // top border
+        let mut board = horiz_border.clone();
+        // for each row (in display order)
+        for row in 0..self.height {
+            let y = self.display_row(row);
+            // left border, each square, right border
+            board += "| ";
+            for x in 0..self.width {
+                // squares outside board's shape are always blank
+                if self.all_squares.contains(&(x, y)) {
+                    board += &format_square(x, y);
+                } else {
+                    board += "  ";
+                }
+            }
+            board += "|\n";
+        }
+        // bottom border
+        board += &horiz_border;
+        board
+    }
+
+    // fn to format a status line of the game so far, like the counters in graphical minesweeper:
+    // the number of mines, flags placed, squares opened and seconds since the first open
+    fn format_status(&self) -> String {
+        format!(
+            "Mines: {} | Flags: {} | Opened: {} | Time: {}s",
+            self.mines.len(),
+            self.flagged_squares.len(),
+            self.open_squares.len(),
+            self.elapsed().as_secs()
+        )
+    }
+
+    // fn to format a minimap of the board, where each char summarises a block of squares
+    // (minimap_scale squares on a side): # if mostly closed, . if mostly open,
+    // @ if it contains the last move, and blank if it has no squares on the board
+    fn format_minimap(&self) -> String {
+        let scale = self.minimap_scale;
+        let (map_width, map_height) = (self.width.div_ceil(scale), self.height.div_ceil(scale));
+        let horiz_border = "+".to_owned() + &"-".repeat(map_width) + "+\n";
+
+        // top border
+        let mut minimap = format!("Minimap ({scale}x{scale} squares per char):\n") + &horiz_border;
+        // for each row of blocks (in display order)
+        for block_row in 0..map_height {
+            minimap += "|";
+            for block_col in 0..map_width {
+                // find squares on the board in this block
+                let rows = block_row * scale..((block_row + 1) * scale).min(self.height);
+                let squares: Vec<Position> = rows
+                    .flat_map(|row| {
+                        let y = self.display_row(row);
+                        (block_col * scale..((block_col + 1) * scale).min(self.width))
+                            .map(move |x| (x, y))
+                    })
+                    .filter(|pos| self.all_squares.contains(pos))
+                    .collect();
+                let open = squares
+                    .iter()
+                    .filter(|pos| self.open_squares.contains(pos))
+                    .count();
+                minimap.push(match self.last_move {
+                    _ if squares.is_empty() => ' ',
+                    Some(pos) if squares.contains(&pos) => '@',
+                    _ if open * 2 > squares.len() => '.',
+                    _ => '#',
+                });
+            }
+            minimap += "|\n";
+        }
+        // bottom border
+        minimap += &horiz_border;
+        minimap
+    }
+}
+
+impl fmt::Display for Minesweeper {
+    // fn to display the board
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // get columns and rows to display
+        let (cols, rows) = self.view_ranges();
+
+        // display status line if asked to, unless paused (as it shows progress)
+        if self.paused.is_some() {
+            writeln!(fmt, "Paused")?;
+        } else if self.show_status {
+            writeln!(fmt, "{}", self.format_status())?;
+        }
+
+        // if only part of the board is displayed, label which part (in 1-based move coords)
+        if cols.len() < self.width || rows.len() < self.height {
+            // get board rows at either end of displayed rows
+            let (first_y, last_y) = (self.display_row(rows.start), self.display_row(rows.end - 1));
+            writeln!(
+                fmt,
+                "Showing x {}-{} of {}, y {}-{} of {}",
+                cols.start + 1,
+                cols.end,
+                self.width,
+                first_y.min(last_y) + 1,
+                first_y.max(last_y) + 1,
+                self.height
+            )?;
+        }
+
+        // generate horizontal border eg.
+        // +--------+ for a board of width 4
+        // each cell takes up 2 chars
+        let horiz_border = "+".to_owned() + &"-".repeat(cols.len() * 2 + 1) + "+\n";
+
+        // display the name of each col above the board if naming cols with letters
+        if self.show_letters {
+            let names: Vec<String> = cols.clone().map(column_name).collect();
+            write!(fmt, "{}", format_col_labels(&names))?;
+        }
+
+        // display top border
+        write!(fmt, "{horiz_border}")?;
+
+        // for each row (in display order)
+        for row in rows {
+            // get board row to display
+            let y = self.display_row(row);
+            // display left border
+            write!(fmt, "| ")?;
+            // for each col
+            for x in cols.clone() {
+                // display square at that pos (or a closed square if paused, to hide the board)
+                if self.paused.is_some() {
+                    write!(fmt, "{}", self.theme.closed)?;
+                } else {
+                    self.write_square(fmt, x, y)?;
+                }
+            }
+            // display right border, followed by row's mine count if displaying rulers
+            if self.show_rulers {
+                writeln!(
+                    fmt,
+                    "| {}",
+                    self.mines.iter().filter(|&&(_, my)| my == y).count()
+                )?;
+            } else {
+                writeln!(fmt, "|")?;
+            }
+        }
+
+        // display bottom border
+        write!(fmt, "{horiz_border}")?;
+        // display each col's mine count below the board if displaying rulers
+        if self.show_rulers {
+            write!(fmt, "{}", self.format_col_ruler(cols))?;
+        }
+        // display lives left if playing with lives
+        if let Some(lives) = self.lives {
+            writeln!(fmt, "Lives: {lives}")?;
+        }
+        // display helps left if playing with helps
+        if let Some(helps) = self.helps {
+            writeln!(fmt, "Helps: {helps}")?;
+        }
+        Ok(())
+    }
+}
+
+// struct to store which symmetries a board's mine layout has (all, not just the first found)
+#[derive(Debug, PartialEq)]
+pub struct Symmetry {
+    pub horizontal: bool, // mirrored top to bottom (across a horizontal line)
+    pub vertical: bool,   // mirrored left to right (across a vertical line)
+    pub rotational: bool, // unchanged by a half turn
+}
+
+impl fmt::Display for Symmetry {
+    // fn to display the symmetries, eg. "horizontal, vertical" or "none"
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let symmetries: Vec<&str> = [
+            (self.horizontal, "horizontal"),
+            (self.vertical, "vertical"),
+            (self.rotational, "rotational"),
+        ]
+        .into_iter()
+        .filter_map(|(present, name)| present.then_some(name))
+        .collect();
+        match symmetries.is_empty() {
+            true => write!(fmt, "none"),
+            false => write!(fmt, "{}", symmetries.join(", ")),
+        }
+    }
+}
+
+// struct to store how far the player is through flagging every mine
+#[derive(Debug, PartialEq)]
+pub struct FlagProgress {
+    pub correct: usize,           // number of flags on mines
+    pub incorrect: Vec<Position>, // flags on safe squares, in reading order
+    pub remaining: usize,         // number of mines not flagged yet
+}
+
+// struct to store results of simulating random play on a board
+struct SimulationStats {
+    games: usize,               // number of games simulated
+    seed: u64,                  // seed squares were picked with, to simulate the same games again
+    wins: usize,                // number of games won
+    clicks_to_loss: Vec<usize>, // number of clicks taken to lose, for each lost game
+}
+
+impl fmt::Display for SimulationStats {
+    // fn to display the stats
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // guard to check any games were simulated
+        if self.games == 0 {
+            return write!(fmt, "No games simulated.");
+        }
+
+        // display win rate
+        writeln!(
+            fmt,
+            "Simulated {} games (seed {}): won {} ({:.1}%)",
+            self.games,
+            self.seed,
+            self.wins,
+            self.wins as f64 * 100.0 / self.games as f64
+        )?;
+
+        // guard to check any games were lost
+        if self.clicks_to_loss.is_empty() {
+            return write!(fmt, "No games lost.");
+        }
+
+        // display distribution of clicks to loss
+        let mut clicks = self.clicks_to_loss.clone();
+        clicks.sort_unstable();
+        let mean = clicks.iter().sum::<usize>() as f64 / clicks.len() as f64;
+        write!(
+            fmt,
+            "Clicks to loss: mean {mean:.2}, min {}, median {}, max {}",
+            clicks[0],
+            clicks[clicks.len() / 2],
+            clicks[clicks.len() - 1]
+        )
+    }
+}
+
+// struct to store the settings for a particular game:
+// board width, board height, number of mines, game variant, display options
+#[derive(Clone)]
+pub struct GameSettings {
+    pub board_width: usize,
+    pub board_height: usize,
+    pub num_mines: usize,
+    pub variant: MinesweeperVariant,
+    pub split_variant: Option<MinesweeperVariant>,
+    pub flip_y: bool,
+    pub safe_radius: Option<usize>,
+    pub min_mine_spacing: Option<usize>,
+    pub no_edge_mines: bool,
+    pub quiet: bool,
+    pub prompts: Prompts,
+    pub show_clusters: bool,
+    pub show_rulers: bool,
+    pub show_status: bool,
+    pub show_letters: bool,
+    pub pause_stops_timer: bool,
+    pub show_guesses: bool,
+    pub warn_isolated: bool,
+    pub assist: bool,
+    pub trace_solver: bool,
+    pub no_guessing: bool,
+    pub required_technique: Option<Technique>,
+    pub seed: Option<u64>,
+    pub record: Option<String>,
+    pub history: Option<String>,
+    pub html: Option<String>,
+    pub mbf: Option<String>,
+    pub leaderboard: Option<String>,
+    pub player: String,
+    pub redraw: bool,
+    pub debug: bool,
+    pub easy: bool,
+    pub spectate: bool,
+    pub auto_guess: Option<GuessStrategy>,
+    pub cascade_clears_flags: bool,
+    pub max_cascade: Option<usize>,
+    pub fog: Option<usize>,
+    pub open_flagged: OpenFlagged,
+    pub viewport: Option<(usize, usize)>,
+    pub minimap_scale: usize,
+    pub detective: bool,
+    pub wrong_flags_lose: bool,
+    pub flag_to_win: bool,
+    pub win_condition: Option<WinCondition>,
+    pub explore: bool,
+    pub theme: Theme,
+    pub lives: Option<usize>,
+    pub helps: Option<usize>,
+    pub autosave: Option<String>,
+    pub shape: Option<HashSet<Position>>,
+    pub animate: Option<Duration>,
+    pub pre_open: Option<PreOpen>,
+    pub transforms: Vec<Transform>,
+}
+
+// impl default settings: a beginner's board (9x9 with 10 mines) of the normal variant,
+// with every option as it is when its flag isn't passed
+impl Default for GameSettings {
+    fn default() -> Self {
+        Self {
+            board_width: 9,
+            board_height: 9,
+            num_mines: 10,
+            variant: MinesweeperVariant::Normal,
+            split_variant: None,
+            flip_y: false,
+            safe_radius: None,
+            min_mine_spacing: None,
+            no_edge_mines: false,
+            quiet: false,
+            prompts: Prompts::default(),
+            show_clusters: false,
+            show_rulers: false,
+            show_status: false,
+            show_letters: false,
+            pause_stops_timer: false,
+            show_guesses: false,
+            warn_isolated: false,
+            assist: false,
+            trace_solver: false,
+            no_guessing: false,
+            required_technique: None,
+            seed: None,
+            record: None,
+            history: None,
+            html: None,
+            mbf: None,
+            leaderboard: None,
+            player: DEFAULT_PLAYER.to_owned(),
+            redraw: false,
+            debug: false,
+            easy: false,
+            spectate: false,
+            auto_guess: None,
+            cascade_clears_flags: false,
+            max_cascade: None,
+            fog: None,
+            open_flagged: OpenFlagged::Ignore,
+            viewport: None,
+            minimap_scale: DEFAULT_MINIMAP_SCALE,
+            detective: false,
+            wrong_flags_lose: false,
+            flag_to_win: false,
+            win_condition: None,
+            explore: false,
+            theme: Theme::CLASSIC,
+            lives: None,
+            helps: None,
+            autosave: None,
+            shape: None,
+            animate: None,
+            pre_open: None,
+            transforms: Vec::new(),
+        }
+    }
+}
+
+impl GameSettings {
+    // fn to construct the settings for a board of the given size, number of mines and variant,
+    // with every other option as it is by default (for playing the game from elsewhere)
+    pub fn new(
+        board_width: usize,
+        board_height: usize,
+        num_mines: usize,
+        variant: MinesweeperVariant,
+    ) -> Self {
+        Self {
+            board_width,
+            board_height,
+            num_mines,
+            variant,
+            ..Default::default()
+        }
+    }
+}
+
+// fn to fetch an arg from command line args
+// takes the position the arg should be in, the arg name,
+// a function/closure to validate it with, and an err msg
+// to display if the validation fails.
+fn get_arg<T, E>(
+    pos: usize,
+    arg_name: &str,
+    validation_fn: fn(String) -> Result<T, E>,
+    err_msg: &str,
+) -> T {
+    // get arg at position pos, erring if absent
+    let nth_arg =
+        std::env::args() // get command line args
+            .nth(pos) // get arg in pos position
+            .unwrap_or_else(|| panic!("parameter {arg_name} expected in position {pos}")); // err if not found
+
+    // validate arg and show err_msg on fail
+    validation_fn(nth_arg) // validate arg
+        .unwrap_or_else(|_| panic!("invalid string found for parameter {arg_name}: {err_msg}"))
+    // err with err_msg on fail
+}
+
+// fn to check whether a flag was passed on the command line
+// flags come after the positional args, eg. `minesweeper 9 9 10 normal --flip-y`
+fn has_flag(flag_name: &str) -> bool {
+    std::env::args() // get command line args
+        .skip(5) // skip program name and positional args
+        .any(|arg| arg == flag_name) // check for flag
+}
+
+// fn to parse a square given as a flag's value, as its 1-based x and y eg. "3,4"
+fn parse_square_arg(raw: String) -> Result<Position, MoveValidationError> {
+    let (raw_x, raw_y) = raw.split_once(',').ok_or(MoveValidationError)?;
+    match (raw_x.parse::<usize>(), raw_y.parse::<usize>()) {
+        (Ok(x), Ok(y)) if x > 0 && y > 0 => Ok((x - 1, y - 1)),
+        _ => Err(MoveValidationError),
+    }
+}
+
+// fn to validate a player's name given as a flag's value
+// (tabs and line breaks would split their score's line of the leaderboard file)
+fn parse_player_name(name: String) -> Result<String, ()> {
+    match name.contains(['\t', '\n', '\r']) {
+        true => Err(()),
+        false => Ok(name),
+    }
+}
+
+// fn to fetch the value following a flag from command line args, if the flag was passed
+// takes the flag name and a function/closure to validate the value with,
+// erring if the value is absent or invalid.
+fn get_flag_value<T, E>(flag_name: &str, validation_fn: fn(String) -> Result<T, E>) -> Option<T> {
+    // find position of flag (after positional args), returning none if absent
+    let pos = std::env::args() // get command line args
+        .skip(5) // skip program name and positional args
+        .position(|arg| arg == flag_name)? // find flag
+        + 5; // account for skipped args
+
+    // get value following flag, erring if absent
+    let value = std::env::args() // get command line args
+        .nth(pos + 1) // get arg after flag
+        .unwrap_or_else(|| panic!("value expected after flag {flag_name}")); // err if not found
+
+    // validate value and err on fail
+    Some(
+        validation_fn(value).unwrap_or_else(|_| panic!("invalid value found for flag {flag_name}")),
+    )
+}
+
+// name scores are recorded under on the leaderboard if the player doesn't give one
+const DEFAULT_PLAYER: &str = "anonymous";
+
+// squares on a side of each block shown by the minimap, if not given
+const DEFAULT_MINIMAP_SCALE: usize = 4;
+
+// maximum number of candidate boards to try when generating a no-guess board
+const MAX_NO_GUESS_TRIES: usize = 10_000;
+
+// number of random safe squares opened by each help
+const HELP_SQUARES: usize = 3;
+
+// maximum number of random orders of squares to try when spacing mines apart
+const MAX_SPACING_TRIES: usize = 100;
+
+// largest width and height of a board which can be written as a .mbf file
+// (each is stored in a byte)
+const MBF_MAX_SIZE: usize = 255;
+
+// percentage of squares to fill with mines when number of mines is "auto"
+const AUTO_MINE_PERCENTAGE: usize = 15;
+
+// levels of campaign mode, in order of difficulty: board width, board height, mines and variant
+const CAMPAIGN_LEVELS: [(usize, usize, usize, MinesweeperVariant); 8] = [
+    (9, 9, 10, MinesweeperVariant::Normal),
+    (12, 12, 20, MinesweeperVariant::Normal),
+    (16, 16, 40, MinesweeperVariant::Normal),
+    (16, 16, 40, MinesweeperVariant::Orthogonal),
+    (16, 16, 45, MinesweeperVariant::BlindUp),
+    (20, 16, 55, MinesweeperVariant::KnightPaths),
+    (24, 20, 70, MinesweeperVariant::FarNormal),
+    (30, 16, 99, MinesweeperVariant::Normal),
+];
+
+// mines of the tutorial's 5x5 board, placed so that every step can be worked out
+const TUTORIAL_MINES: [Position; 3] = [(4, 1), (4, 3), (2, 4)];
+
+// steps of the tutorial, in order: an explanation of what the board shows, then the move the
+// player is asked to make (on the square given). squares in explanations are 1-based move coords
+const TUTORIAL_STEPS: [(&str, MoveType, Position); 6] = [
+    (
+        "Each move, choose a square by its x (column, from the left) and y (row, from the top), \
+         then what to do with it. Start by opening the top left square: enter 1, 1, then open.",
+        MoveType::Open,
+        (0, 0),
+    ),
+    (
+        "Squares with no mines near are blank, and open their neighbors for you (a cascade). \
+         Numbers show how many mines are next to a square, diagonals included. The 1 at (2, 4) \
+         has only one closed square next to it, so (3, 5) must be a mine: flag it.",
+        MoveType::Flag,
+        (2, 4),
+    ),
+    (
+        "The 1 at (3, 4) already has its mine, the flag at (3, 5), so its other closed \
+         neighbor is safe: open (4, 5).",
+        MoveType::Open,
+        (3, 4),
+    ),
+    (
+        "The 1 at (4, 1) has its mine at (5, 1) or (5, 2). Both are also next to the 1 at \
+         (4, 2), so that is its mine too, and its other closed neighbor is safe: open (5, 3).",
+        MoveType::Open,
+        (4, 2),
+    ),
+    (
+        "The 2 at (5, 3) has exactly two closed squares next to it, so both are mines: \
+         flag (5, 2).",
+        MoveType::Flag,
+        (4, 1),
+    ),
+    ("And flag (5, 4), the other one.", MoveType::Flag, (4, 3)),
+];
+
+// fn to suggest a reasonable number of mines for a board with a given number of squares
+// (AUTO_MINE_PERCENTAGE of squares, rounded to the nearest mine)
+fn suggested_mine_count(num_squares: usize) -> usize {
+    // calculated in u128 so that a large board can't overflow the intermediate product
+    ((num_squares as u128 * AUTO_MINE_PERCENTAGE as u128 + 50) / 100) as usize
+}
+
+// fn to get the most mines a board with a given number of squares can have
+// (one square must be left safe, or the first open would always lose)
+fn max_mine_count(num_squares: usize) -> usize {
+    num_squares.saturating_sub(1)
+}
+
+// fn to choose a viewport which fits the board to the terminal, if displaying to a terminal
+// and the whole board doesn't fit. each square takes up 2 chars, plus 3 for the border,
+// and 6 rows are left for the border, the viewport label and the move prompt.
+// (unless quiet, the player is told the board is shown a viewport at a time)
+fn fitting_viewport(board_width: usize, board_height: usize, quiet: bool) -> Option<Position> {
+    // guard to check if displaying to a terminal
+    if !stdout().is_terminal() {
+        return None;
+    }
+    let (cols, rows) = terminal_size();
+    let (view_width, view_height) = (
+        (cols.saturating_sub(3) / 2).max(1),
+        rows.saturating_sub(6).max(1),
+    );
+    // guard to check if the board doesn't fit
+    if board_width <= view_width && board_height <= view_height {
+        return None;
+    }
+    if !quiet {
+        println!("Board doesn't fit the terminal: showing {view_width}x{view_height} at a time.");
+    }
+    Some((view_width, view_height))
+}
+
+// fn to parse a board shape from a text template, where # marks a square on the board
+// and anything else (eg. .) marks a hole. the board's size is the smallest rectangle
+// containing every square. returns none if the template has no squares.
+fn parse_shape(template: &str) -> Option<HashSet<Position>> {
+    let shape: HashSet<Position> = template
+        .lines()
+        .enumerate()
+        .flat_map(|(y, line)| {
+            line.chars()
+                .enumerate()
+                .filter(|&(_, c)| c == '#')
+                .map(move |(x, _)| (x, y))
+        })
+        .collect();
+    (!shape.is_empty()).then_some(shape)
+}
+
+// fn to parse a custom variant from its name and the offsets of the squares it counts mines in,
+// eg. "wide:-2,0;-1,0;1,0;2,0" for the squares up to 2 left or right of the square
+fn parse_custom_variant(spec: String) -> Result<(String, Vec<(isize, isize)>), ()> {
+    let (name, raw_offsets) = spec.split_once(':').ok_or(())?;
+    let offsets = raw_offsets
+        .split(';')
+        .map(|raw_offset| {
+            let (raw_dx, raw_dy) = raw_offset.split_once(',').ok_or(())?;
+            Ok((
+                raw_dx.trim().parse().map_err(|_| ())?,
+                raw_dy.trim().parse().map_err(|_| ())?,
+            ))
+        })
+        .collect::<Result<_, ()>>()?;
+    Ok((name.trim().to_lowercase(), offsets))
+}
+
+// fn to build a GameSettings object from cmd line args
+fn get_game_settings() -> GameSettings {
+    // custom variant, registered so it can be chosen (and loaded) like the built-in variants
+    // (registered variants last until the program exits, so their name can be leaked)
+    if let Some((name, offsets)) = get_flag_value("--custom-variant", parse_custom_variant) {
+        let description = format!("all mines at offsets {offsets:?} from square");
+        MinesweeperVariant::register(
+            Box::leak(name.into_boxed_str()),
+            Box::leak(description.into_boxed_str()),
+            move || offsets.clone(),
+        )
+        .unwrap_or_else(|err| panic!("invalid custom variant: {err}"));
+    }
+    // leave out prompts and extra messages, only displaying the board and results
+    let quiet = has_flag("--quiet");
+    // board width
+    let board_width = get_arg(
+        1,
+        "board_width",
+        |x| x.parse::<usize>(),
+        "unable to parse to usize",
+    );
+    // board height
+    let board_height = get_arg(
+        2,
+        "board_height",
+        |x| x.parse::<usize>(),
+        "unable to parse to usize",
+    );
+    // board shape, loaded from a template file (overrides board width and height)
+    let shape = get_flag_value("--shape", std::fs::read_to_string).map(|template| {
+        parse_shape(&template).unwrap_or_else(|| panic!("shape template contains no squares"))
+    });
+    let (board_width, board_height) = match &shape {
+        Some(shape) => (
+            shape.iter().map(|&(x, _)| x + 1).max().unwrap_or(0),
+            shape.iter().map(|&(_, y)| y + 1).max().unwrap_or(0),
+        ),
+        None => (board_width, board_height),
+    };
+    // number of squares on board
+    let num_squares = match &shape {
+        Some(shape) => shape.len(),
+        None => Minesweeper::count_squares(board_width, board_height).unwrap_or_else(|_| {
+            panic!("board of size {board_width}x{board_height} is too big or has no squares")
+        }),
+    };
+    // number of mines ("auto" to use a suggested number for the board size)
+    let num_mines = get_arg(
+        3,
+        "num_mines",
+        |x| match x.to_lowercase().as_str() {
+            "auto" => Ok(None),
+            _ => x.parse::<usize>().map(Some),
+        },
+        "unable to parse to usize or auto",
+    )
+    .unwrap_or_else(|| {
+        let num_mines = suggested_mine_count(num_squares);
+        if !quiet {
+            println!("Using {num_mines} mines ({AUTO_MINE_PERCENTAGE}% of squares).");
+        }
+        num_mines
+    });
+    // check all mines fit on the board, leaving a square to open
+    if num_mines > max_mine_count(num_squares) {
+        panic!(
+            "too many mines: at most {} fit on the board",
+            max_mine_count(num_squares)
+        );
+    }
+
+    // game variant
+    let variant = get_arg(
+        4,
+        "variant",
+        |x| x.parse::<MinesweeperVariant>(),
+        &format!(
+            "invalid variant: allowed variants include:{}",
+            MinesweeperVariant::list()
+        ),
+    );
+    // variants of the left and right halves of the board, if split (overrides the variant)
+    let split = get_flag_value("--split-variant", |x| {
+        let (left, right) = x.split_once(':').ok_or(VariantParseError)?;
+        Ok::<_, VariantParseError>((left.parse()?, right.parse()?))
+    });
+    // path to write a .mbf file to, checking the board fits in one
+    let mbf = get_flag_value("--mbf", Ok::<String, ()>);
+    if mbf.is_some() && board_width.max(board_height) > MBF_MAX_SIZE {
+        panic!("board too big for a .mbf file: at most {MBF_MAX_SIZE}x{MBF_MAX_SIZE} fits");
+    }
+
+    // build GameSettings object
+    let settings = GameSettings {
+        board_width,
+        board_height,
+        num_mines,
+        variant: split.map_or(variant, |(left, _)| left),
+        split_variant: split.map(|(_, right)| right),
+        // display row 1 at the bottom
+        flip_y: has_flag("--flip-y"),
+        // display mine clusters at game end
+        show_clusters: has_flag("--show-clusters"),
+        // display the number of mines in each row and col along the board's edges
+        show_rulers: has_flag("--rulers"),
+        // display mines, flags, opened squares and time taken above the board
+        show_status: has_flag("--status"),
+        // name cols spreadsheet-style with letters, so squares can be entered like "B3"
+        show_letters: has_flag("--letters"),
+        // leave time spent paused out of the time taken
+        pause_stops_timer: has_flag("--pause-stops-timer"),
+        // review the mine probabilities at each guess at game end
+        show_guesses: has_flag("--show-guesses"),
+        // warn of safe squares which must be opened directly, once the board is set
+        warn_isolated: has_flag("--warn-isolated"),
+        // after each open or flag, tell the player if no square can be proven safe or a mine,
+        // and after each flag, warn them if more are next to numbers than the numbers allow
+        assist: has_flag("--assist"),
+        // write a trace of the solver's decisions to stderr
+        trace_solver: has_flag("--trace-solver"),
+        // make the board solvable without guessing from the first open
+        // (and needing a given technique to solve, if one is required)
+        no_guessing: has_flag("--no-guessing") || has_flag("--require-technique"),
+        required_technique: get_flag_value("--require-technique", |x| x.parse::<Technique>()),
+        // seed to generate mines from, and start searching for a no-guess board from
+        // (a random one is chosen if recording a replay, so the board can be generated again)
+        seed: get_flag_value("--seed", |x| x.parse::<u64>())
+            .or_else(|| has_flag("--record").then(random_seed)),
+        // path to write a replay of the game to at game end
+        record: get_flag_value("--record", Ok::<String, ()>),
+        // path to write the moves made, and when, to at game end
+        history: get_flag_value("--history", Ok::<String, ()>),
+        // path to write the final board to as an html table at game end
+        html: get_flag_value("--html", Ok::<String, ()>),
+        // path to write the board to as a .mbf file at game end
+        mbf,
+        // path of leaderboard file to record a win in (and display the top scores from)
+        leaderboard: get_flag_value("--leaderboard", Ok::<String, ()>),
+        // name of the player, for the leaderboard
+        player: get_flag_value("--name", parse_player_name).unwrap_or(DEFAULT_PLAYER.to_owned()),
+        // redraw the board in place each move (only if displaying to a terminal)
+        redraw: has_flag("--redraw") && stdout().is_terminal(),
+        // make debug commands available
+        debug: has_flag("--debug"),
+        // make easy mode hints (like the distance to the nearest mine) available
+        easy: has_flag("--easy"),
+        // let the solver play, only asking the player to move when a guess is needed
+        spectate: has_flag("--spectate"),
+        // let the solver guess too when spectating, choosing between the safest squares this way
+        auto_guess: get_flag_value("--auto-guess", |x| x.parse::<GuessStrategy>()),
+        // let cascades open flagged squares (clearing the flags) instead of stopping at them
+        cascade_clears_flags: has_flag("--cascade-clears-flags"),
+        // most squares a cascade can open beyond the square opened, eg. 0 for no cascades
+        max_cascade: get_flag_value("--max-cascade", |x| x.parse::<usize>()),
+        // distance from the square opened past which squares a cascade opens are fogged, showing
+        // ? until opened directly
+        fog: get_flag_value("--fog", |x| x.parse::<usize>()),
+        // what opening a flagged square does: ignore (the default), warn, or unflag and open
+        open_flagged: get_flag_value("--open-flagged", |x| x.parse::<OpenFlagged>())
+            .unwrap_or(OpenFlagged::Ignore),
+        // size of window of board to display, eg. 20x10
+        // (chosen to fit the terminal if not given and the board doesn't fit)
+        viewport: get_flag_value("--viewport", |x| {
+            x.split_once('x')
+                .ok_or(SizeValidationError)
+                .and_then(|(width, height)| Minesweeper::validate_size(width, height))
+        })
+        .or_else(|| fitting_viewport(board_width, board_height, quiet)),
+        // squares on a side of each block summarised by one char of the minimap (default 4)
+        minimap_scale: get_flag_value("--minimap-scale", |x| match x.parse::<usize>() {
+            Ok(0) => Err(SizeValidationError),
+            scale => scale.map_err(|_| SizeValidationError),
+        })
+        .unwrap_or(DEFAULT_MINIMAP_SCALE),
+        // show the number of mines near each correctly flagged mine
+        detective: has_flag("--detective"),
+        // in detective mode, lose the game on flagging a safe square
+        wrong_flags_lose: has_flag("--wrong-flags-lose"),
+        // once every safe square is open, have the player flag every mine to win
+        flag_to_win: has_flag("--flag-to-win"),
+        // win by opening this square (eg. 3,4) rather than every safe square
+        // (or by opening every safe square anyway, in case it turns out to be a mine)
+        win_condition: get_flag_value("--target", parse_square_arg).map(|target| -> WinCondition {
+            if target.0 >= board_width || target.1 >= board_height {
+                panic!("target square is off the board");
+            }
+            Arc::new(move |game| game.open_squares.contains(&target) || game.safe_squares_open())
+        }),
+        // after winning, let the player open and flag the mines left before the game ends
+        explore: has_flag("--explore"),
+        // glyphs, colors and terminology to display with (colors only if displaying to a terminal)
+        // with numbers colored from a palette instead of the theme's own colors if one is given
+        theme: {
+            let theme =
+                get_flag_value("--theme", Ok::<String, ()>).map_or(Theme::CLASSIC, |name| {
+                    name.parse::<Theme>().unwrap_or_else(|_| {
+                        panic!("invalid theme: allowed themes are {}", Theme::list())
+                    })
+                });
+            let theme = get_flag_value("--palette", Ok::<String, ()>).map_or(theme, |name| {
+                theme.with_palette(name.parse::<Palette>().unwrap_or_else(|_| {
+                    panic!("invalid palette: allowed palettes are {}", Palette::list())
+                }))
+            });
+            if stdout().is_terminal() {
+                theme
+            } else {
+                theme.without_colors()
+            }
+        },
+        // number of mines that can be hit (each flagged instead) before the game is lost
+        lives: get_flag_value("--lives", |x| match x.parse::<usize>() {
+            Ok(0) => Err(SizeValidationError),
+            lives => lives.map_err(|_| SizeValidationError),
+        }),
+        // number of times random safe squares can be opened with the help command
+        helps: get_flag_value("--helps", |x| x.parse::<usize>()),
+        // path to save game to after every move
+        autosave: get_flag_value("--autosave", Ok::<String, ()>),
+        // radius around first open guaranteed to be free of mines
+        safe_radius: get_flag_value("--safe-radius", |x| x.parse::<usize>()),
+        // minimum distance between mines (1 for no minimum, 2 for no mines next to each other)
+        min_mine_spacing: get_flag_value("--min-mine-spacing", |x| match x.parse::<usize>() {
+            Ok(0) => Err(SizeValidationError),
+            spacing => spacing.map_err(|_| SizeValidationError),
+        }),
+        // keep mines out of the outermost ring of squares
+        no_edge_mines: has_flag("--no-edge-mines"),
+        quiet,
+        // prompts to display instead of the move x, move y and move type prompts
+        prompts: Prompts {
+            x: get_flag_value("--x-prompt", Ok::<String, ()>),
+            y: get_flag_value("--y-prompt", Ok::<String, ()>),
+            move_type: get_flag_value("--move-prompt", Ok::<String, ()>),
+        },
+        shape,
+        // delay between revealing each mine on a loss, in milliseconds
+        animate: get_flag_value("--animate", |x| x.parse::<u64>().map(Duration::from_millis)),
+        // squares to open at the start: a number of random safe squares, "zero" for a cascade,
+        // or "corner" for the safest corner
+        pre_open: get_flag_value("--pre-open", |x| x.parse::<PreOpen>()),
+        // rotations and reflections to apply to the board, in order, eg. rotate,flip-vertical
+        // (for variations of a seeded board)
+        transforms: get_flag_value("--transform", |x| {
+            x.split(',')
+                .map(|transform| transform.parse::<Transform>())
+                .collect()
+        })
+        .unwrap_or_default(),
+    };
+
+    // check there is room for all mines outside the safe area, wherever the first open is
+    if let Some(radius) = settings.safe_radius {
+        // safe area is largest when first open is far from the edges
+        let safe_width = (radius * 2 + 1).min(settings.board_width);
+        let safe_height = (radius * 2 + 1).min(settings.board_height);
+        let free_cells = num_squares.saturating_sub(safe_width * safe_height);
+        if settings.num_mines > free_cells {
+            panic!("too many mines for safe radius {radius}: at most {free_cells} fit outside it");
+        }
+    }
+    // check there is room for all mines away from the edges (outside the safe area, if any)
+    if settings.no_edge_mines {
+        let (width, height) = (settings.board_width, settings.board_height);
+        let squares = Minesweeper::settings_squares(&settings);
+        let interior = Minesweeper::interior_squares(&squares, width, height).len();
+        // safe area is largest when first open is in the middle of the interior
+        let safe_side = |side: usize| {
+            settings
+                .safe_radius
+                .map_or(0, |radius| (radius * 2 + 1).min(side.saturating_sub(2)))
+        };
+        let free_cells = interior.saturating_sub(safe_side(width) * safe_side(height));
+        if settings.num_mines > free_cells {
+            panic!("too many mines to keep off the edges: at most {free_cells} fit away from them");
+        }
+    }
+
+    // return settings
+    settings
+}
+
+// fn to get a yes/no answer from the player
+fn get_confirmation(msg: &str, quiet: bool) -> bool {
+    match get_input(msg, None, quiet).to_lowercase().trim() {
+        "y" | "yes" => true,
+        "n" | "no" => false,
+        // invalid - try again
+        _ => get_confirmation(msg, quiet),
+    }
+}
+
+// fn to init a game from settings, offering to resume the autosaved game if there is one
+fn resume_autosave(settings: GameSettings) -> Minesweeper {
+    // guard to check if there is an autosave to resume
+    let Some(path) = settings.autosave.clone() else {
+        return Minesweeper::new(settings);
+    };
+    if !std::path::Path::new(&path).exists()
+        || !get_confirmation("Autosaved game found. Resume it? (y/n): ", settings.quiet)
+    {
+        return Minesweeper::new(settings);
+    }
+
+    // load autosave, starting a new game if that fails
+    match std::fs::read_to_string(&path) {
+        Ok(save) => Minesweeper::load_from_string(&save, settings.clone()).unwrap_or_else(|err| {
+            println!("Warning: unable to resume autosave: {err}");
+            Minesweeper::new(settings)
+        }),
+        Err(err) => {
+            println!("Warning: unable to read autosave from {path}: {err}");
+            Minesweeper::new(settings)
+        }
+    }
+}
+
+// fn to play campaign mode: each level's board in turn, advancing on a win and ending on a loss
+// (or quit). the board size, mines and variant come from the level, everything else from the
+// settings. each level won scores its number of mines.
+fn play_campaign(settings: GameSettings) {
+    let mut score = 0;
+    for (i, &(board_width, board_height, num_mines, variant)) in CAMPAIGN_LEVELS.iter().enumerate()
+    {
+        let level = i + 1;
+        println!(
+            "Level {level} of {}: {board_width}x{board_height} {} with {num_mines} {}s",
+            CAMPAIGN_LEVELS.len(),
+            variant.name(),
+            settings.theme.mine_word
+        );
+        let mut minesweeper = Minesweeper::new(GameSettings {
+            board_width,
+            board_height,
+            num_mines,
+            variant,
+            shape: None,
+            ..settings.clone()
+        });
+        minesweeper.play();
+        // guard to check if the level was won, ending the campaign at this level if not
+        match minesweeper.state() {
+            GameState::Won => {}
+            GameState::Quit => {
+                println!("Campaign quit at level {level}. Final score: {score}");
+                return;
+            }
+            _ => {
+                println!("Campaign over: reached level {level}. Final score: {score}");
+                return;
+            }
+        }
+        score += num_mines;
+        println!("Score: {score}");
+    }
+    println!("Campaign complete! Final score: {score}");
+}
+
+// fn to play the tutorial: a fixed board, with each step explained and the player asked for a
+// particular move, then left to finish the board. board options come from the tutorial,
+// everything else from the settings
+fn play_tutorial(settings: GameSettings) {
+    let mut game = Minesweeper::new_with_mines(
+        GameSettings {
+            board_width: 5,
+            board_height: 5,
+            variant: MinesweeperVariant::Normal,
+            split_variant: None,
+            shape: None,
+            flip_y: false,
+            safe_radius: None,
+            no_guessing: false,
+            viewport: None,
+            ..settings
+        },
+        HashSet::from(TUTORIAL_MINES),
+    );
+    let mine_word = game.theme.mine_word;
+    println!(
+        "Welcome to minesweeper! Open every square without a {mine_word} to win \
+         (or enter q to quit)."
+    );
+    for (explanation, move_type, (x, y)) in TUTORIAL_STEPS {
+        game.redraw();
+        println!("{}", explanation.replace("mine", mine_word));
+        // get moves until the player makes the one asked for, stopping if they asked to quit
+        loop {
+            let Some(positions) = game.get_move_pos() else {
+                return;
+            };
+            match game.get_move_type() {
+                MoveType::Quit => return,
+                chosen if chosen == move_type && positions[0] == (x, y) => break,
+                _ => println!(
+                    "Not quite - {} ({}, {}).",
+                    recorded_move_name(move_type),
+                    x + 1,
+                    y + 1
+                ),
+            }
+        }
+        match move_type {
+            MoveType::Open => game.apply(Action::Open((x, y))),
+            _ => game.apply(Action::Flag((x, y))),
+        };
+    }
+    println!("Every {mine_word} is flagged, so the squares left are safe: open them to win!");
+    game.play();
+}
+
+// fn to play the game from the command line args
+pub fn run() {
+    // if asked to list variants, do so and exit
+    if std::env::args().any(|arg| arg == "--list-variants") {
+        println!("Variants:{}", MinesweeperVariant::list());
+        return;
+    }
+    // get game settings from cmd line args
+    let settings = get_game_settings();
+    // if asked to verify a replay, do so and exit
+    if let Some(replay) = get_flag_value("--verify-replay", std::fs::read_to_string) {
+        match Minesweeper::parse_replay(&replay) {
+            Ok((seed, moves, outcome))
+                if Minesweeper::verify_replay(settings, seed, &moves, &outcome) =>
+            {
+                println!("Replay verified: the moves {} the game", outcome.name());
+            }
+            Ok(_) => println!("Replay invalid: the moves don't give the claimed outcome"),
+            Err(_) => println!("Replay invalid: missing or malformed fields"),
+        }
+        return;
+    }
+    // if asked to continue a replay, rewind it to the given move (or its end) and play on
+    if let Some(replay) = get_flag_value("--continue-replay", std::fs::read_to_string) {
+        match Minesweeper::parse_replay(&replay) {
+            Ok((seed, moves, _)) => {
+                let n = get_flag_value("--from-move", |x| x.parse::<usize>());
+                let mut game =
+                    Minesweeper::replay_to(settings, seed, &moves, n.unwrap_or(moves.len()));
+                game.play();
+            }
+            Err(_) => println!("Replay invalid: missing or malformed fields"),
+        }
+        return;
+    }
+    // if asked to play a board from a .mbf file, do so and exit
+    if let Some(mbf) = get_flag_value("--from-mbf", std::fs::read) {
+        match Minesweeper::from_mbf(&mbf, settings) {
+            Ok(mut game) => game.play(),
+            Err(_) => println!("Invalid .mbf file: missing or malformed fields"),
+        }
+        return;
+    }
+    // if asked to play a board given by its numbers, do so and exit
+    if let Some(clues) = get_flag_value("--from-clues", std::fs::read_to_string) {
+        match Minesweeper::from_clues(&clues, settings) {
+            Ok(mut game) => game.play(),
+            Err(_) => println!("Invalid clues: malformed, or no mines give the numbers"),
+        }
+        return;
+    }
+    // if asked to play several boards at once, do so and exit
+    if let Some(num_boards) = get_flag_value("--boards", |x| match x.parse::<usize>() {
+        Ok(0) => Err(SizeValidationError),
+        num_boards => num_boards.map_err(|_| SizeValidationError),
+    }) {
+        MultiBoard::new(settings, num_boards).play();
+        return;
+    }
+    // if asked to display the leaderboard for these settings, do so and exit
+    if has_flag("--show-leaderboard") {
+        let Some(path) = settings.leaderboard.clone() else {
+            println!("No leaderboard file given: use --leaderboard PATH");
+            return;
+        };
+        let board = Minesweeper::new(settings).leaderboard_board();
+        match leaderboard::load(&path) {
+            Ok(scores) => println!("{}", leaderboard::format_top(&scores, &board)),
+            Err(err) => println!("Unable to read leaderboard from {path}: {err}"),
+        }
+        return;
+    }
+    // if asked to play the tutorial, do so and exit
+    if has_flag("--tutorial") {
+        play_tutorial(settings);
+        return;
+    }
+    // if asked to play campaign mode, do so and exit
+    if has_flag("--campaign") {
+        play_campaign(settings);
+        return;
+    }
+    // init game with these settings, or resume autosaved game
+    let mut minesweeper = resume_autosave(settings);
+    // if asked to simulate random play, do so and exit
+    // (starting each game by opening --simulate-from X,Y if given, and picking squares with
+    // the seed setting, or a random one)
+    if let Some(num_games) = get_flag_value("--simulate", |x| x.parse::<usize>()) {
+        let opening = get_flag_value("--simulate-from", parse_square_arg).inspect(|&(x, y)| {
+            if !minesweeper.all_squares.contains(&(x, y)) {
+                panic!("square to simulate from is off the board");
+            }
+        });
+        let seed = minesweeper.seed.unwrap_or_else(random_seed);
+        println!("{}", minesweeper.simulate(num_games, opening, seed));
+        return;
+    }
+    // play game
+    minesweeper.play();
+    // if the player quit, let them know the game can be picked up again
+    if let (GameState::Quit, Some(path)) = (minesweeper.state(), &minesweeper.autosave) {
+        if !minesweeper.quiet {
+            println!("Game saved to {path}, run again with --autosave {path} to resume.");
+        }
+    }
+}
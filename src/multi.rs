@@ -0,0 +1,169 @@
+// multi-board mode: several small boards played at once, displayed side by side and sharing a
+// budget of mines. each move is made on one board, chosen by its number.
+// the game is won once every board is won, and lost as soon as any board is lost.
+
+use crate::{get_input, Action, GameSettings, GameState, Minesweeper, MoveType};
+use std::fmt;
+
+// gap between boards displayed side by side
+const BOARD_GAP: &str = "   ";
+
+// struct to store the boards of a multi-board game
+pub struct MultiBoard {
+    pub boards: Vec<Minesweeper>, // boards, from left to right
+}
+
+impl MultiBoard {
+    // fn to construct a game of num_boards boards from an instance of GameSettings, each of
+    // its size. its mines are split between the boards as evenly as possible (with any left
+    // over going to the leftmost boards), and each board is generated from its own seed
+    // (the seed setting plus the board's index, if given)
+    pub fn new(settings: GameSettings, num_boards: usize) -> Self {
+        let boards = (0..num_boards)
+            .map(|i| {
+                let num_mines = settings.num_mines / num_boards
+                    + usize::from(i < settings.num_mines % num_boards);
+                Minesweeper::new(GameSettings {
+                    num_mines,
+                    seed: settings.seed.map(|seed| seed.wrapping_add(i as u64)),
+                    ..settings.clone()
+                })
+            })
+            .collect();
+        Self { boards }
+    }
+
+    // fn to get the state of the whole game: lost if any board is lost, won if every board
+    // is won, otherwise still playing
+    pub fn state(&self) -> GameState {
+        let states = || self.boards.iter().map(|board| board.state());
+        if states().any(|state| state == GameState::Lost) {
+            GameState::Lost
+        } else if states().all(|state| state == GameState::Won) {
+            GameState::Won
+        } else {
+            GameState::Playing
+        }
+    }
+
+    // fn to get a valid board number from the player, as an index into the boards
+    // returns none if the player asked to quit
+    fn get_board(&self) -> Option<usize> {
+        let raw = get_input(
+            &format!("Enter board (1-{}): ", self.boards.len()),
+            None,
+            self.boards[0].quiet,
+        );
+        match raw.trim() {
+            "q" | "quit" => None,
+            raw => match raw.parse::<usize>() {
+                // boards which are over can't be played on
+                Ok(n) if (1..=self.boards.len()).contains(&n) => {
+                    if self.boards[n - 1].state() == GameState::Won {
+                        println!("Board {n} is already won.");
+                        self.get_board()
+                    } else {
+                        Some(n - 1)
+                    }
+                }
+                // invalid - try again
+                _ => {
+                    println!("Invalid board.");
+                    self.get_board()
+                }
+            },
+        }
+    }
+
+    // fn to display the boards, followed by any notices from them since they were last displayed
+    fn display(&mut self) {
+        println!("{self}");
+        for board in &mut self.boards {
+            board.print_notices();
+        }
+    }
+
+    // fn to play the boards until every board is won, or one is lost
+    // only opening and flagging are available, as other commands only fit a single board
+    pub fn play(&mut self) {
+        self.display();
+        while self.state() == GameState::Playing {
+            // get board, then move pos(es) and type on it, stopping if the player asked to quit
+            let Some(i) = self.get_board() else {
+                break;
+            };
+            let board = &mut self.boards[i];
+            let Some(positions) = board.get_move_pos() else {
+                break;
+            };
+            match board.get_move_type() {
+                MoveType::Open => {
+                    for &(x, y) in &positions {
+                        board.apply(Action::Open((x, y)));
+                    }
+                }
+                MoveType::Flag => {
+                    for &(x, y) in &positions {
+                        board.apply(Action::Flag((x, y)));
+                    }
+                }
+                MoveType::Quit => break,
+                _ => {
+                    println!("Only open, flag and quit are available with multiple boards.");
+                    continue;
+                }
+            }
+            self.display();
+        }
+        // display result
+        match self.state() {
+            GameState::Won => println!("You won every board!"),
+            GameState::Lost => println!("You lost!"),
+            GameState::Playing | GameState::Quit => {
+                if !self.boards[0].quiet {
+                    println!("Quitting...");
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for MultiBoard {
+    // fn to display the boards side by side, each labelled with its number
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // display each board on its own, lined up to the widest of its lines
+        let displayed: Vec<(Vec<String>, usize)> = self
+            .boards
+            .iter()
+            .enumerate()
+            .map(|(i, board)| {
+                let lines: Vec<String> = [format!("Board {}", i + 1)]
+                    .into_iter()
+                    .chain(board.to_string().lines().map(String::from))
+                    .collect();
+                let width = lines
+                    .iter()
+                    .map(|line| board.theme.display_width(line))
+                    .max()
+                    .unwrap_or(0);
+                (lines, width)
+            })
+            .collect();
+        // display each line of every board in turn, padding boards with fewer lines
+        let height = displayed
+            .iter()
+            .map(|(lines, _)| lines.len())
+            .max()
+            .unwrap_or(0);
+        for row in 0..height {
+            let mut line = String::new();
+            for ((lines, width), board) in displayed.iter().zip(&self.boards) {
+                let part = lines.get(row).map_or("", String::as_str);
+                let padding = width - board.theme.display_width(part);
+                line += &format!("{part}{}{BOARD_GAP}", " ".repeat(padding));
+            }
+            writeln!(fmt, "{}", line.trim_end())?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,68 @@
+// the game's own small random number generator, used for everything random in the game
+// (placing mines, moving mines out of the safe area, random play).
+// it is seeded, so a seed always gives the same board, and doesn't need rand:
+// rand is only used (behind the "rand" feature) as a source of entropy and for injected rngs.
+
+#[cfg(feature = "rand")]
+use rand::prelude::*;
+#[cfg(not(feature = "rand"))]
+use std::collections::hash_map::RandomState;
+#[cfg(not(feature = "rand"))]
+use std::hash::BuildHasher;
+
+// struct to store the state of a splitmix64 generator
+#[derive(Clone)]
+pub struct SeededRng {
+    state: u64, // current state, advanced on every number generated
+}
+
+impl SeededRng {
+    // fn to construct a generator from a seed
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    // fn to construct a generator from a random seed
+    pub fn from_entropy() -> Self {
+        Self::new(random_seed())
+    }
+
+    // fn to generate the next random u64
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    // fn to generate a random index below bound (which must be nonzero)
+    // the random u64 is scaled down to the bound, rather than taken modulo it
+    pub fn gen_index(&mut self, bound: usize) -> usize {
+        ((u128::from(self.next_u64()) * bound as u128) >> 64) as usize
+    }
+
+    // fn to choose amount distinct random items from a slice, in random order
+    // (if there aren't enough items, all of them are chosen)
+    pub fn choose_multiple<T: Copy>(&mut self, items: &[T], amount: usize) -> Vec<T> {
+        // shuffle the start of the items, one random pick at a time
+        let mut items = items.to_vec();
+        let amount = amount.min(items.len());
+        for i in 0..amount {
+            let j = i + self.gen_index(items.len() - i);
+            items.swap(i, j);
+        }
+        items.truncate(amount);
+        items
+    }
+}
+
+// fn to get a random seed
+// this comes from rand's thread rng if rand is enabled, otherwise from the random keys
+// std generates for hash maps (which are seeded from the operating system)
+pub fn random_seed() -> u64 {
+    #[cfg(feature = "rand")]
+    return thread_rng().gen();
+    #[cfg(not(feature = "rand"))]
+    return RandomState::new().hash_one(());
+}
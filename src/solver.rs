@@ -0,0 +1,965 @@
+// the solver deduces which closed squares must be mines and which must be safe
+// using only what a player can see: the numbers on open squares.
+// flags are ignored, as they are only the player's guesses.
+
+use crate::{GameState, Minesweeper, Position};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+
+// fn to write lines of the solver trace to stderr
+// lines are written as one block, so that traces of boards being solved on different threads
+// (when generating a no-guess board) don't interleave
+fn trace(lines: &[String]) {
+    let block: String = lines
+        .iter()
+        .map(|line| format!("solver: {line}\n"))
+        .collect();
+    eprint!("{block}");
+}
+
+// fn to format positions for the solver trace, as 1-based x,y pairs separated by ;
+fn trace_positions(positions: &[Position]) -> String {
+    positions
+        .iter()
+        .map(|(x, y)| format!("{},{}", x + 1, y + 1))
+        .collect::<Vec<String>>()
+        .join(";")
+}
+
+// enum to store the techniques the solver can use, from simplest to most advanced
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Technique {
+    Single, // a single number is satisfied by its closed neighbors alone
+    Subset, // one number's closed neighbors are a subset of another's
+}
+
+impl Technique {
+    // all techniques, from simplest to most advanced
+    const ALL: [Self; 2] = [Self::Single, Self::Subset];
+
+    // fn to get the name of a technique
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Single => "single",
+            Self::Subset => "subset",
+        }
+    }
+}
+
+// err to raise if parse from str fails
+#[derive(Debug, PartialEq)]
+pub struct TechniqueParseError;
+
+impl fmt::Display for TechniqueParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Invalid technique")
+    }
+}
+
+// impl ability to parse from str
+impl FromStr for Technique {
+    // err to return if parsing fails
+    type Err = TechniqueParseError;
+
+    // fn to parse technique from str, by name
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.to_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|technique| technique.name() == name)
+            .ok_or(TechniqueParseError)
+    }
+}
+
+// enum to store how the auto-guesser chooses between the squares least likely to be mines
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GuessStrategy {
+    Corner,      // the square with the fewest neighbors (the most likely to have no mines near)
+    Information, // the square expected to open the most squares
+    Position,    // the first square in reading order
+}
+
+impl GuessStrategy {
+    // all strategies
+    const ALL: [Self; 3] = [Self::Corner, Self::Information, Self::Position];
+
+    // fn to get the name of a strategy (as used on the command line)
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Corner => "corner",
+            Self::Information => "information",
+            Self::Position => "position",
+        }
+    }
+}
+
+// err to raise if parse from str fails
+#[derive(Debug, PartialEq)]
+pub struct GuessStrategyParseError;
+
+impl fmt::Display for GuessStrategyParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Invalid guess strategy")
+    }
+}
+
+// impl ability to parse from str
+impl FromStr for GuessStrategy {
+    // err to return if parsing fails
+    type Err = GuessStrategyParseError;
+
+    // fn to parse guess strategy from str, by name
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.to_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|strategy| strategy.name() == name)
+            .ok_or(GuessStrategyParseError)
+    }
+}
+
+// struct to store the closed squares split by whether an open number counts them,
+// with how many of the mines (that aren't flagged) are in each part
+#[derive(Debug, PartialEq)]
+pub struct FrontierPartition {
+    pub frontier: HashSet<Position>, // closed squares counted by an open number
+    pub interior: HashSet<Position>, // closed squares not counted by any open number
+    pub frontier_mines: usize,       // unflagged mines on the frontier
+    pub interior_mines: usize,       // unflagged mines in the interior
+}
+
+// difference in chance of being a mine below which two squares count as equally likely
+// (chances are calculated in floating point, so equal chances may differ slightly)
+const EQUAL_CHANCE: f64 = 1e-9;
+
+// most squares in a group of numbers to search every arrangement of, when bounding the mines
+// on the frontier or finding each square's chance of being a mine
+// (the search takes exponential time, so is too slow for larger groups)
+const MAX_BOUND_SQUARES: usize = 24;
+
+// struct to store a constraint from an open square: the unknown squares counted by its number
+// (with how many times each is counted, as some variants count squares twice)
+// and how many mines must be among them
+#[derive(Clone)]
+struct Constraint {
+    source: Position,                  // open square the constraint comes from
+    squares: HashMap<Position, usize>, // unknown squares, and how many times each is counted
+    mines: usize,                      // number of mines among them (counted the same way)
+}
+
+impl Constraint {
+    // fn to apply the single-square rule to a constraint
+    // all squares are mines if they must all be to make up the number,
+    // and any square counted more times than there are mines left must be safe.
+    // returns the squares found to be safe and the squares found to be mines.
+    fn deduce(&self) -> (Vec<Position>, Vec<Position>) {
+        let total_weight: usize = self.squares.values().sum();
+        // every unknown square must be a mine
+        if self.mines == total_weight {
+            return (Vec::new(), self.squares.keys().copied().collect());
+        }
+        // every square counted more times than there are mines must be safe
+        // (this is all of them if there are no mines left)
+        let safe = self
+            .squares
+            .iter()
+            .filter(|&(_, &weight)| weight > self.mines)
+            .map(|(&pos, _)| pos)
+            .collect();
+        (safe, Vec::new())
+    }
+
+    // fn to subtract a constraint whose squares are a subset of this one's
+    // returns none if the other constraint isn't a subset, or the numbers are inconsistent
+    fn subtract(&self, other: &Self) -> Option<Self> {
+        // check other's squares are all counted by this constraint, the same number of times
+        let is_subset = other
+            .squares
+            .iter()
+            .all(|(pos, weight)| self.squares.get(pos) == Some(weight));
+        if !is_subset || other.squares.len() == self.squares.len() {
+            return None;
+        }
+        // remaining squares must contain the remaining mines
+        Some(Self {
+            source: self.source,
+            squares: self
+                .squares
+                .iter()
+                .filter(|(pos, _)| !other.squares.contains_key(pos))
+                .map(|(&pos, &weight)| (pos, weight))
+                .collect(),
+            mines: self.mines.checked_sub(other.mines)?,
+        })
+    }
+}
+
+// struct to store a single step in the solution of a board:
+// which technique was used on which numbers, and what it proved
+#[derive(Clone, Debug, PartialEq)]
+pub struct DeductionStep {
+    pub technique: Technique,   // technique used
+    pub sources: Vec<Position>, // open squares whose numbers were used
+    pub safe: Vec<Position>,    // squares proven safe
+    pub mines: Vec<Position>,   // squares proven to be mines
+}
+
+impl fmt::Display for DeductionStep {
+    // fn to display the step, eg. "single: the number at (1, 2) means (2, 3) is a mine"
+    // positions are displayed as 1-based move coords
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // fn to format a list of positions
+        let format_positions = |positions: &[Position]| {
+            positions
+                .iter()
+                .map(|(x, y)| format!("({}, {})", x + 1, y + 1))
+                .collect::<Vec<String>>()
+                .join(", ")
+        };
+        let sources = format_positions(&self.sources);
+        match self.sources.len() {
+            1 => write!(
+                fmt,
+                "{}: the number at {sources} means",
+                self.technique.name()
+            )?,
+            _ => write!(
+                fmt,
+                "{}: the numbers at {sources} mean",
+                self.technique.name()
+            )?,
+        }
+        match self.mines.len() {
+            0 => {}
+            1 => write!(fmt, " {} is a mine", format_positions(&self.mines))?,
+            _ => write!(fmt, " {} are mines", format_positions(&self.mines))?,
+        }
+        if !self.mines.is_empty() && !self.safe.is_empty() {
+            write!(fmt, " and")?;
+        }
+        match self.safe.len() {
+            0 => {}
+            1 => write!(fmt, " {} is safe", format_positions(&self.safe))?,
+            _ => write!(fmt, " {} are safe", format_positions(&self.safe))?,
+        }
+        Ok(())
+    }
+}
+
+impl Minesweeper {
+    // fn to build constraints from the numbers on all open squares
+    // squares in known_mines are treated as already-found mines rather than unknowns,
+    // and squares in known_safe as safe (though their numbers are still hidden).
+    // constraints are returned in reading order of their open square, so solving is deterministic
+    fn constraints(
+        &self,
+        known_mines: &HashSet<Position>,
+        known_safe: &HashSet<Position>,
+    ) -> Vec<Constraint> {
+        let mut sources: Vec<(Position, usize)> = self.open_cells().collect();
+        sources.sort_by_key(|&((x, y), _)| (y, x));
+        sources
+            .into_iter()
+            .filter_map(|((x, y), mines_near)| {
+                // count unknown neighbors and known mine neighbors
+                let mut squares = HashMap::<Position, usize>::new();
+                let mut found_mines = 0;
+                for neighbor in self.neighbors_iter(x, y) {
+                    if known_mines.contains(&neighbor) {
+                        found_mines += 1;
+                    } else if !self.open_squares.contains(&neighbor)
+                        && !known_safe.contains(&neighbor)
+                    {
+                        *squares.entry(neighbor).or_default() += 1;
+                    }
+                }
+                // ignore numbers with no unknown neighbors, as they can't tell us anything
+                if squares.is_empty() {
+                    return None;
+                }
+                Some(Constraint {
+                    source: (x, y),
+                    squares,
+                    mines: mines_near.saturating_sub(found_mines),
+                })
+            })
+            .collect()
+    }
+
+    // fn to find the next deduction that can be made from the current open squares,
+    // using techniques up to and including max_technique (simplest techniques are tried first)
+    pub fn next_deduction(
+        &self,
+        known_mines: &HashSet<Position>,
+        known_safe: &HashSet<Position>,
+        max_technique: Technique,
+    ) -> Option<DeductionStep> {
+        // fn to turn a (safe, mines) deduction into a step, if anything was deduced
+        let to_step =
+            |technique, sources, (mut safe, mut mines): (Vec<Position>, Vec<Position>)| {
+                if safe.is_empty() && mines.is_empty() {
+                    return None;
+                }
+                // sort for deterministic output
+                safe.sort_by_key(|&(x, y)| (y, x));
+                mines.sort_by_key(|&(x, y)| (y, x));
+                Some(DeductionStep {
+                    technique,
+                    sources,
+                    safe,
+                    mines,
+                })
+            };
+        let constraints = self.constraints(known_mines, known_safe);
+
+        // single-square rule on each number alone
+        for constraint in &constraints {
+            if let Some(step) = to_step(
+                Technique::Single,
+                vec![constraint.source],
+                constraint.deduce(),
+            ) {
+                return Some(step);
+            }
+        }
+
+        // subset rule on each pair of numbers
+        if max_technique >= Technique::Subset {
+            for larger in &constraints {
+                for smaller in &constraints {
+                    let Some(difference) = larger.subtract(smaller) else {
+                        continue;
+                    };
+                    if let Some(step) = to_step(
+                        Technique::Subset,
+                        vec![larger.source, smaller.source],
+                        difference.deduce(),
+                    ) {
+                        return Some(step);
+                    }
+                }
+            }
+        }
+
+        // no deduction found
+        None
+    }
+
+    // fn to get the ordered chain of deductions that solves the board from its current state
+    // each deduction's safe squares are opened (revealing new numbers) before the next is found.
+    // the chain stops when the board is won or no more deductions can be made (a guess is needed).
+    pub fn solution_steps(&self) -> Vec<DeductionStep> {
+        self.solve().0
+    }
+
+    // fn to check whether the board can be won from its current state without guessing
+    pub fn is_solvable(&self) -> bool {
+        self.solve().1 == GameState::Won
+    }
+
+    // fn to find the most advanced technique needed to win the board from its current state
+    // without guessing (the simplest technique if nothing needs deducing), or none if it can't be
+    // won without guessing. simpler techniques are always tried first, so if a technique is
+    // used at all, the board can't be won with simpler ones alone.
+    pub fn needed_technique(&self) -> Option<Technique> {
+        let (steps, state) = self.solve();
+        (state == GameState::Won).then(|| {
+            steps
+                .iter()
+                .map(|step| step.technique)
+                .max()
+                .unwrap_or(Technique::Single)
+        })
+    }
+
+    // fn to check whether any closed square can be proven safe or a mine from the numbers
+    // shown now (stopping at the first one found, so cheaper than finding them all)
+    pub fn has_certain_move(&self) -> bool {
+        self.next_deduction(&HashSet::new(), &HashSet::new(), Technique::Subset)
+            .is_some()
+    }
+
+    // fn to find every closed square that can be proven safe or a mine from the numbers shown now,
+    // using techniques up to and including max_technique. unlike solving, proven safe squares
+    // aren't opened, so nothing is learnt from the numbers under them.
+    // returns the squares proven safe and the squares proven to be mines.
+    pub fn determinable_cells(
+        &self,
+        max_technique: Technique,
+    ) -> (HashSet<Position>, HashSet<Position>) {
+        let mut safe = HashSet::<Position>::new();
+        let mut mines = HashSet::<Position>::new();
+        // keep deducing until nothing more can be proven
+        // (each deduction proves squares not already known, so this always finishes)
+        while let Some(step) = self.next_deduction(&mines, &safe, max_technique) {
+            safe.extend(step.safe);
+            mines.extend(step.mines);
+        }
+        (safe, mines)
+    }
+
+    // fn to find the fewest mines which explain the given numbers, as if they were shown on open
+    // squares (the board's own mines and open squares are ignored). only squares counted by a
+    // number can be mines, as any others wouldn't be needed.
+    // returns none if no arrangement of mines gives every number
+    pub fn infer_mines_from_numbers(
+        &self,
+        numbers: &HashMap<Position, usize>,
+    ) -> Option<HashSet<Position>> {
+        // build a constraint from each number, on the squares counted which aren't numbered
+        let mut sources: Vec<(Position, usize)> = numbers.iter().map(|(&p, &n)| (p, n)).collect();
+        sources.sort_by_key(|&((x, y), _)| (y, x));
+        let constraints: Vec<Constraint> = sources
+            .into_iter()
+            .map(|((x, y), number)| {
+                let mut squares = HashMap::<Position, usize>::new();
+                for neighbor in self.neighbors_iter(x, y) {
+                    if !numbers.contains_key(&neighbor) {
+                        *squares.entry(neighbor).or_default() += 1;
+                    }
+                }
+                Constraint {
+                    source: (x, y),
+                    squares,
+                    mines: number,
+                }
+            })
+            .collect();
+        // guard to check every number can be made up by the squares it counts
+        // (the arrangement search assumes this to start with)
+        if constraints
+            .iter()
+            .any(|constraint| constraint.mines > constraint.squares.values().sum())
+        {
+            return None;
+        }
+        // fewest mines for each group, failing if a group has no arrangement
+        let mut mines = HashSet::<Position>::new();
+        for group in group_constraints(&constraints) {
+            let arrangements = Arrangements::find(&group);
+            let fewest = arrangements.fewest?;
+            mines.extend(fewest.into_iter().map(|i| arrangements.squares[i]));
+        }
+        Some(mines)
+    }
+
+    // fn to solve a copy of the board as far as possible without guessing
+    // returns the chain of deductions made, and the state of the board afterwards
+    // (writing a trace of each decision to stderr, if the game's settings ask for one)
+    fn solve(&self) -> (Vec<DeductionStep>, GameState) {
+        let (steps, state, trace_lines) = self.solve_traced(self.trace_solver);
+        if self.trace_solver {
+            trace(&trace_lines);
+        }
+        (steps, state)
+    }
+
+    // fn to solve a copy of the board as far as possible without guessing, as solve does,
+    // also returning a trace of each decision as lines of key=value pairs if tracing
+    // (the trace is only built when tracing, so it costs nothing otherwise)
+    pub fn solve_traced(&self, tracing: bool) -> (Vec<DeductionStep>, GameState, Vec<String>) {
+        // solve a copy of the board
+        let mut game = self.clone();
+        let mut known_mines = HashSet::<Position>::new();
+        let mut steps = Vec::<DeductionStep>::new();
+        let mut trace_lines = Vec::<String>::new();
+        if tracing {
+            trace_lines.push(format!(
+                "start open={} closed={}",
+                game.open_squares.len(),
+                game.all_squares.len() - game.open_squares.len()
+            ));
+        }
+        while game.state() == GameState::Playing {
+            // find next deduction, stopping if there isn't one
+            let Some(step) = game.next_deduction(&known_mines, &HashSet::new(), Technique::Subset)
+            else {
+                break;
+            };
+            // open safe squares and record mines
+            for &(x, y) in &step.safe {
+                // flags aren't trusted, so remove any in the way
+                // (ignore err as deduced squares are on the board)
+                game.flagged_squares.remove(&(x, y));
+                let _ = game.open(x, y);
+            }
+            known_mines.extend(step.mines.iter().copied());
+            // every deduction is certain, as the solver never guesses
+            if tracing {
+                trace_lines.push(format!(
+                    "step={} technique={} sources={} safe={} mines={} confidence=certain",
+                    steps.len() + 1,
+                    step.technique.name(),
+                    trace_positions(&step.sources),
+                    trace_positions(&step.safe),
+                    trace_positions(&step.mines)
+                ));
+            }
+            steps.push(step);
+        }
+        // trace the outcome: solved, or stuck needing a guess
+        if tracing {
+            let closed = game.all_squares.len() - game.open_squares.len();
+            trace_lines.push(match game.state() {
+                GameState::Won => format!("solved steps={}", steps.len()),
+                _ => format!(
+                    "stuck steps={} closed={closed} known_mines={}",
+                    steps.len(),
+                    known_mines.len()
+                ),
+            });
+        }
+        // return steps, final state and trace
+        (steps, game.state(), trace_lines)
+    }
+}
+
+// struct to store every arrangement of mines that satisfies a group of connected constraints
+// (counts are stored as floats, as there can be too many arrangements to fit in an integer)
+struct Arrangements {
+    squares: Vec<Position>,     // squares counted by the constraints
+    totals: Vec<f64>,           // number of arrangements with each number of mines
+    mine_counts: Vec<Vec<f64>>, // for each number of mines, how many have each square a mine
+    fewest: Option<Vec<usize>>, // an arrangement with the fewest mines (if there are any)
+}
+
+impl Arrangements {
+    // fn to find every arrangement of mines satisfying the given constraints
+    fn find(constraints: &[&Constraint]) -> Self {
+        // number the squares, and link each to the constraints counting it (and how many times)
+        let mut squares: Vec<Position> = constraints
+            .iter()
+            .flat_map(|constraint| constraint.squares.keys().copied())
+            .collect::<HashSet<Position>>()
+            .into_iter()
+            .collect();
+        squares.sort_by_key(|&(x, y)| (y, x));
+        let links: Vec<Vec<(usize, usize)>> = squares
+            .iter()
+            .map(|pos| {
+                constraints
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, constraint)| Some((i, *constraint.squares.get(pos)?)))
+                    .collect()
+            })
+            .collect();
+
+        // search through every arrangement, starting with no squares decided
+        let mut arrangements = Self {
+            totals: vec![0.0; squares.len() + 1],
+            mine_counts: vec![vec![0.0; squares.len()]; squares.len() + 1],
+            fewest: None,
+            squares,
+        };
+        let mut needed: Vec<usize> = constraints
+            .iter()
+            .map(|constraint| constraint.mines)
+            .collect();
+        let mut undecided: Vec<usize> = constraints
+            .iter()
+            .map(|constraint| constraint.squares.values().sum())
+            .collect();
+        arrangements.search(&links, &mut needed, &mut undecided, &mut Vec::new());
+        arrangements
+    }
+
+    // fn to approximate the arrangements of a group too large to search, as a single arrangement
+    // spreading its mines evenly over its squares (with at most the given number of mines).
+    // each square's chance of being a mine is estimated as the average share of the mines
+    // of the numbers counting it, and the group's mines as the sum of these chances
+    fn estimate(constraints: &[&Constraint], most_mines: usize) -> Self {
+        let mut chances = HashMap::<Position, Vec<f64>>::new();
+        for constraint in constraints {
+            let share = constraint.mines as f64 / constraint.squares.values().sum::<usize>() as f64;
+            for &pos in constraint.squares.keys() {
+                chances.entry(pos).or_default().push(share);
+            }
+        }
+        let mut squares: Vec<Position> = chances.keys().copied().collect();
+        squares.sort_by_key(|&(x, y)| (y, x));
+        let estimate: f64 = chances
+            .values()
+            .map(|shares| shares.iter().sum::<f64>() / shares.len() as f64)
+            .sum();
+        let mines = (estimate.round() as usize)
+            .min(squares.len())
+            .min(most_mines);
+
+        // the one arrangement has each square a mine by the same fraction
+        let mut totals = vec![0.0; squares.len() + 1];
+        totals[mines] = 1.0;
+        let mut mine_counts = vec![vec![0.0; squares.len()]; squares.len() + 1];
+        mine_counts[mines] = vec![mines as f64 / squares.len() as f64; squares.len()];
+        Self {
+            squares,
+            totals,
+            mine_counts,
+            fewest: None,
+        }
+    }
+
+    // fn to count every arrangement of mines on the undecided squares, given the mines chosen
+    // so far. needed and undecided track how many more mines each constraint needs and the
+    // weight of its undecided squares, so that impossible choices are abandoned straight away
+    fn search(
+        &mut self,
+        links: &[Vec<(usize, usize)>],
+        needed: &mut [usize],
+        undecided: &mut [usize],
+        chosen: &mut Vec<usize>,
+    ) {
+        // split off the next square to decide, recording the arrangement if there are none left
+        let Some((square_links, rest)) = links.split_first() else {
+            self.totals[chosen.len()] += 1.0;
+            for &square in chosen.iter() {
+                self.mine_counts[chosen.len()][square] += 1.0;
+            }
+            if self
+                .fewest
+                .as_ref()
+                .is_none_or(|fewest| chosen.len() < fewest.len())
+            {
+                self.fewest = Some(chosen.clone());
+            }
+            return;
+        };
+        let square = self.squares.len() - links.len();
+        // the square is no longer undecided
+        for &(i, weight) in square_links {
+            undecided[i] -= weight;
+        }
+
+        // try the square as safe: every constraint must still be able to get its mines
+        if square_links.iter().all(|&(i, _)| needed[i] <= undecided[i]) {
+            self.search(rest, needed, undecided, chosen);
+        }
+        // try the square as a mine: no constraint can have more mines than it needs
+        if square_links.iter().all(|&(i, weight)| needed[i] >= weight) {
+            for &(i, weight) in square_links {
+                needed[i] -= weight;
+            }
+            chosen.push(square);
+            self.search(rest, needed, undecided, chosen);
+            chosen.pop();
+            for &(i, weight) in square_links {
+                needed[i] += weight;
+            }
+        }
+
+        // undo deciding the square
+        for &(i, weight) in square_links {
+            undecided[i] += weight;
+        }
+    }
+}
+
+// fn to group constraints connected by the squares they share
+// (so that each group can be solved independently)
+fn group_constraints(constraints: &[Constraint]) -> Vec<Vec<&Constraint>> {
+    let mut groups = Vec::<Vec<&Constraint>>::new();
+    let mut grouped = vec![false; constraints.len()];
+    for start in 0..constraints.len() {
+        // guard to check if constraint is already in a group
+        if grouped[start] {
+            continue;
+        }
+        grouped[start] = true;
+        // collect all constraints connected to this one
+        let mut group = vec![&constraints[start]];
+        let mut next = 0;
+        while let Some(current) = group.get(next).copied() {
+            for (i, other) in constraints.iter().enumerate() {
+                if !grouped[i]
+                    && other
+                        .squares
+                        .keys()
+                        .any(|pos| current.squares.contains_key(pos))
+                {
+                    grouped[i] = true;
+                    group.push(other);
+                }
+            }
+            next += 1;
+        }
+        groups.push(group);
+    }
+    groups
+}
+
+// fn to find the squares counted by a group of constraints
+fn group_squares(group: &[&Constraint]) -> HashSet<Position> {
+    group
+        .iter()
+        .flat_map(|constraint| constraint.squares.keys().copied())
+        .collect()
+}
+
+// fn to bound the mines in a group of constraints by their numbers, as max_frontier_mines does
+fn number_bound(group: &[&Constraint]) -> usize {
+    let mut numbers = group.to_vec();
+    numbers.sort_by_key(|constraint| {
+        let (x, y) = constraint.source;
+        (constraint.mines, y, x)
+    });
+    let mut taken = HashSet::<Position>::new();
+    numbers
+        .into_iter()
+        .map(|constraint| {
+            let squares = constraint
+                .squares
+                .keys()
+                .filter(|&&pos| taken.insert(pos))
+                .count();
+            squares.min(constraint.mines)
+        })
+        .sum()
+}
+
+// fn to combine the distributions of the number of mines in two groups of squares
+fn convolve(a: &[f64], b: &[f64]) -> Vec<f64> {
+    let mut combined = vec![0.0; a.len() + b.len() - 1];
+    for (i, &a_count) in a.iter().enumerate() {
+        for (j, &b_count) in b.iter().enumerate() {
+            combined[i + j] += a_count * b_count;
+        }
+    }
+    combined
+}
+
+// fn to calculate the natural log of n choose k (the number of ways to place k mines on n squares)
+fn ln_choose(n: usize, k: usize) -> f64 {
+    (0..k)
+        .map(|i| ((n - i) as f64).ln() - ((i + 1) as f64).ln())
+        .sum()
+}
+
+impl Minesweeper {
+    // fn to calculate the chance of each closed square being a mine, from what the player can see:
+    // the numbers on open squares and the total number of mines.
+    // every arrangement of mines consistent with these is counted as equally likely.
+    // closed squares are grouped into independent groups of squares sharing numbers
+    // (plus the squares not next to any number), and every arrangement of each group is counted.
+    // groups with too many squares to search are estimated instead, with the same chance for
+    // each of their squares
+    pub fn mine_probabilities(&self) -> HashMap<Position, f64> {
+        // find every arrangement of each group of constraints connected by the squares they share
+        let constraints = self.constraints(&HashSet::new(), &HashSet::new());
+        let groups: Vec<Arrangements> = group_constraints(&constraints)
+            .iter()
+            .map(
+                |group| match group_squares(group).len() > MAX_BOUND_SQUARES {
+                    true => Arrangements::estimate(group, self.mines.len()),
+                    false => Arrangements::find(group),
+                },
+            )
+            .collect();
+
+        // find closed squares not next to any number
+        let interior: Vec<Position> = self.frontier_partition().interior.into_iter().collect();
+
+        // fn to weight a number of mines next to numbers by the ways to place the rest elsewhere
+        // (logs are scaled by the largest possible, so weights can't overflow)
+        let total_mines = self.mines.len();
+        let weight = |frontier_mines: usize| match total_mines.checked_sub(frontier_mines) {
+            Some(rest) if rest <= interior.len() => (ln_choose(interior.len(), rest)
+                - ln_choose(interior.len(), interior.len() / 2))
+            .exp(),
+            _ => 0.0,
+        };
+
+        // combine the distributions of all groups, and the distributions of all groups but one
+        let combine = |skip: Option<usize>| {
+            groups
+                .iter()
+                .enumerate()
+                .filter(|&(i, _)| Some(i) != skip)
+                .fold(vec![1.0], |combined, (_, group)| {
+                    convolve(&combined, &group.totals)
+                })
+        };
+        let all = combine(None);
+        let total: f64 = all
+            .iter()
+            .enumerate()
+            .map(|(f, &count)| count * weight(f))
+            .sum();
+        // guard to check the numbers are consistent with some arrangement of mines
+        if total == 0.0 {
+            return HashMap::new();
+        }
+
+        // chance of each square next to a number being a mine:
+        // the weighted proportion of arrangements with it a mine
+        let mut probabilities = HashMap::<Position, f64>::new();
+        for (i, group) in groups.iter().enumerate() {
+            let others = combine(Some(i));
+            for (j, &pos) in group.squares.iter().enumerate() {
+                let mut count = 0.0;
+                for (k, mine_counts) in group.mine_counts.iter().enumerate() {
+                    for (f, &other_count) in others.iter().enumerate() {
+                        count += mine_counts[j] * other_count * weight(k + f);
+                    }
+                }
+                probabilities.insert(pos, count / total);
+            }
+        }
+        // chance of each other square being a mine: the expected share of the remaining mines
+        let interior_mines: f64 = all
+            .iter()
+            .enumerate()
+            .map(|(f, &count)| count * weight(f) * total_mines.saturating_sub(f) as f64)
+            .sum();
+        let interior_chance = interior_mines / total / interior.len() as f64;
+        probabilities.extend(interior.into_iter().map(|pos| (pos, interior_chance)));
+        probabilities
+    }
+    // fn to split the closed squares into the frontier (those counted by an open number) and the
+    // interior (the rest), counting the unflagged mines in each. every interior square has the
+    // same chance of being a mine, as nothing the player can see tells them apart
+    pub fn frontier_partition(&self) -> FrontierPartition {
+        let frontier: HashSet<Position> = self
+            .constraints(&HashSet::new(), &HashSet::new())
+            .into_iter()
+            .flat_map(|constraint| constraint.squares.into_keys())
+            .collect();
+        let interior: HashSet<Position> = self
+            .all_squares
+            .iter()
+            .filter(|pos| !self.open_squares.contains(pos) && !frontier.contains(pos))
+            .copied()
+            .collect();
+        // fn to count the unflagged mines among some squares
+        let count_mines = |squares: &HashSet<Position>| {
+            squares
+                .iter()
+                .filter(|pos| self.mines.contains(pos) && !self.flagged_squares.contains(pos))
+                .count()
+        };
+        FrontierPartition {
+            frontier_mines: count_mines(&frontier),
+            interior_mines: count_mines(&interior),
+            frontier,
+            interior,
+        }
+    }
+
+    // fn to map how hard each closed square is for the solver to mark, as the number of rounds
+    // of deductions it takes: each round proves everything it can from the numbers shown at its
+    // start, then opens the squares proven safe, showing more numbers for the next round.
+    // squares proven in the first round are the easiest, and squares the solver never proves
+    // (without guessing) are left out. squares opened by cascades count as marked in the round
+    // that opened them, and once the board is solved the squares left are mines by elimination.
+    pub fn difficulty_map(&self) -> HashMap<Position, usize> {
+        let mut game = self.clone();
+        let mut rounds = HashMap::<Position, usize>::new();
+        for round in 1.. {
+            // guard to check if the board is already solved
+            if game.state() != GameState::Playing {
+                break;
+            }
+            let (safe, mines) = game.determinable_cells(Technique::Subset);
+            let open_before = game.open_squares.clone();
+            for &(x, y) in &safe {
+                // flags aren't trusted, so remove any in the way
+                // (ignore err as deduced squares are on the board)
+                game.flagged_squares.remove(&(x, y));
+                let _ = game.open(x, y);
+            }
+            // find squares marked for the first time this round, stopping if there are none
+            let mut marked: HashSet<Position> = game
+                .open_squares
+                .difference(&open_before)
+                .copied()
+                .chain(mines)
+                .collect();
+            // once the board is won, the mines left are known too
+            // (but not any safe squares a custom win condition left closed)
+            if game.state() == GameState::Won {
+                marked.extend(
+                    game.mines
+                        .iter()
+                        .filter(|pos| !game.open_squares.contains(pos)),
+                );
+            }
+            marked.retain(|pos| !rounds.contains_key(pos));
+            if marked.is_empty() {
+                break;
+            }
+            rounds.extend(marked.into_iter().map(|pos| (pos, round)));
+        }
+        rounds
+    }
+
+    // fn to find the most mines the open numbers allow on the frontier, from every arrangement
+    // of mines satisfying each group of numbers which share squares. flags aren't trusted, so
+    // flagged squares count as unknowns like any other closed square.
+    // groups with too many squares to search are bounded by their numbers instead: going
+    // through the numbers from smallest to largest, each takes the squares it counts that no
+    // number before it has, and can't have more mines among them than it shows (or than there
+    // are squares). the frontier never has more mines than the board, either
+    pub fn max_frontier_mines(&self) -> usize {
+        let constraints = self.constraints(&HashSet::new(), &HashSet::new());
+        let most: usize = group_constraints(&constraints)
+            .iter()
+            .map(|group| {
+                if group_squares(group).len() > MAX_BOUND_SQUARES {
+                    return number_bound(group);
+                }
+                let totals = Arrangements::find(group).totals;
+                totals.iter().rposition(|&total| total > 0.0).unwrap_or(0)
+            })
+            .sum();
+        most.min(self.mine_count())
+    }
+
+    // fn to choose a closed, unflagged square to guess: one of those least likely to be a mine,
+    // chosen between by the strategy given. returns the square and its chance of being a mine,
+    // or none if there is nothing to guess
+    pub fn best_guess(&self, strategy: GuessStrategy) -> Option<(Position, f64)> {
+        let probabilities = self.mine_probabilities();
+        let candidates: Vec<(Position, f64)> = probabilities
+            .iter()
+            .filter(|(pos, _)| !self.flagged_squares.contains(pos))
+            .map(|(&pos, &chance)| (pos, chance))
+            .collect();
+        let lowest = candidates
+            .iter()
+            .map(|&(_, chance)| chance)
+            .reduce(f64::min)?;
+        // find the squares tied for least likely, in reading order (so ties are broken the same
+        // way every time)
+        let mut tied: Vec<Position> = candidates
+            .into_iter()
+            .filter(|&(_, chance)| chance - lowest < EQUAL_CHANCE)
+            .map(|(pos, _)| pos)
+            .collect();
+        tied.sort_unstable_by_key(|&(x, y)| (y, x));
+        // choose between them (the first in reading order if still tied)
+        let guess = match strategy {
+            GuessStrategy::Corner => tied
+                .into_iter()
+                .min_by_key(|&(x, y)| self.neighbors_iter(x, y).count()),
+            GuessStrategy::Information => tied.into_iter().rev().max_by(|&a, &b| {
+                self.expected_opened(a, &probabilities)
+                    .total_cmp(&self.expected_opened(b, &probabilities))
+            }),
+            GuessStrategy::Position => tied.into_iter().next(),
+        };
+        guess.map(|pos| (pos, lowest))
+    }
+
+    // fn to estimate how many squares opening a closed square would open, if it is safe:
+    // the square itself, plus its closed neighbors if none of them are mines (so it cascades).
+    // the chance of that is estimated as if each neighbor were a mine independently
+    fn expected_opened(&self, (x, y): Position, probabilities: &HashMap<Position, f64>) -> f64 {
+        let closed: Vec<f64> = self
+            .neighbors_iter(x, y)
+            .filter(|&pos| pos != (x, y))
+            .filter_map(|pos| probabilities.get(&pos).copied())
+            .collect();
+        let cascade_chance: f64 = closed.iter().map(|chance| 1.0 - chance).product();
+        1.0 + cascade_chance * closed.len() as f64
+    }
+}
@@ -0,0 +1,51 @@
+// detection of the size of the terminal the game is displayed in, for fitting the board to it.
+// the size is asked for from the terminal itself where possible (on unix, with the
+// "terminal-size" feature), then from the COLUMNS and LINES environment variables,
+// falling back to a standard 80x24 terminal if neither works.
+
+// size of terminal to assume if its size can't be detected (cols, rows)
+const FALLBACK_TERMINAL_SIZE: (usize, usize) = (80, 24);
+
+// fn to get the size of the terminal in chars (cols, rows)
+// this never fails: if the size can't be detected, the fallback size is used
+pub fn terminal_size() -> (usize, usize) {
+    ioctl_size()
+        .or_else(env_size)
+        .unwrap_or(FALLBACK_TERMINAL_SIZE)
+}
+
+// fn to ask the terminal stdout is connected to for its size
+#[cfg(all(unix, feature = "terminal-size"))]
+fn ioctl_size() -> Option<(usize, usize)> {
+    let mut size = libc::winsize {
+        ws_row: 0,
+        ws_col: 0,
+        ws_xpixel: 0,
+        ws_ypixel: 0,
+    };
+    // safety: TIOCGWINSZ only writes a winsize to the pointer given, which is valid for writes
+    let result = unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) };
+    // ignore a size of zero, which some terminals report when they don't know
+    (result == 0 && size.ws_col > 0 && size.ws_row > 0)
+        .then_some((usize::from(size.ws_col), usize::from(size.ws_row)))
+}
+
+// fn to ask the terminal for its size (not possible without the "terminal-size" feature)
+#[cfg(not(all(unix, feature = "terminal-size")))]
+fn ioctl_size() -> Option<(usize, usize)> {
+    None
+}
+
+// fn to get the terminal size from the COLUMNS and LINES environment variables, if both are set
+fn env_size() -> Option<(usize, usize)> {
+    // fn to get a positive number from an environment variable
+    let get_var = |name| {
+        std::env::var(name)
+            .ok()?
+            .trim()
+            .parse::<usize>()
+            .ok()
+            .filter(|&value| value > 0)
+    };
+    Some((get_var("COLUMNS")?, get_var("LINES")?))
+}
@@ -0,0 +1,2137 @@
+// tests for the game: the neighbors each variant counts, and opening/flagging/winning
+
+use super::*;
+
+// fn to make settings for a board with everything else left off
+fn settings(width: usize, height: usize, variant: MinesweeperVariant) -> GameSettings {
+    GameSettings {
+        board_width: width,
+        board_height: height,
+        num_mines: 0,
+        variant,
+        ..Default::default()
+    }
+}
+
+// fn to make a 7x7 board with no mines (big enough for the far variants to reach 2 squares out)
+fn board(variant: MinesweeperVariant) -> Minesweeper {
+    Minesweeper::new_with_mines(settings(7, 7, variant), HashSet::new())
+}
+
+// fn to get a square's neighbors in sorted order, so they can be compared to a list
+fn sorted_neighbors(game: &Minesweeper, x: usize, y: usize) -> Vec<Position> {
+    let mut neighbors = game.neighbors(x, y);
+    neighbors.sort();
+    neighbors
+}
+
+// fn to sort an expected list of neighbors
+fn sorted(mut squares: Vec<Position>) -> Vec<Position> {
+    squares.sort();
+    squares
+}
+
+// fn to get every square in a rectangle, for the variants which count a whole area
+fn area(xs: Range<usize>, ys: Range<usize>) -> Vec<Position> {
+    xs.flat_map(|x| ys.clone().map(move |y| (x, y))).collect()
+}
+
+// fn to check a variant's neighbors at the top left corner (0, 0),
+// the middle of the left edge (0, 3) and the centre of the board (3, 3)
+fn check_neighbors(
+    variant: MinesweeperVariant,
+    corner: Vec<Position>,
+    edge: Vec<Position>,
+    centre: Vec<Position>,
+) {
+    let game = board(variant);
+    assert_eq!(
+        sorted_neighbors(&game, 0, 0),
+        sorted(corner),
+        "{} corner",
+        variant.name()
+    );
+    assert_eq!(
+        sorted_neighbors(&game, 0, 3),
+        sorted(edge),
+        "{} edge",
+        variant.name()
+    );
+    assert_eq!(
+        sorted_neighbors(&game, 3, 3),
+        sorted(centre),
+        "{} centre",
+        variant.name()
+    );
+}
+
+#[test]
+fn normal_neighbors() {
+    check_neighbors(
+        MinesweeperVariant::Normal,
+        vec![(0, 1), (1, 0), (1, 1)],
+        vec![(0, 2), (0, 4), (1, 2), (1, 3), (1, 4)],
+        vec![
+            (2, 2),
+            (2, 3),
+            (2, 4),
+            (3, 2),
+            (3, 4),
+            (4, 2),
+            (4, 3),
+            (4, 4),
+        ],
+    );
+}
+
+#[test]
+fn far_normal_neighbors() {
+    // the 5x5 area includes the square itself
+    check_neighbors(
+        MinesweeperVariant::FarNormal,
+        area(0..3, 0..3),
+        area(0..3, 1..6),
+        area(1..6, 1..6),
+    );
+}
+
+#[test]
+fn knight_paths_neighbors() {
+    check_neighbors(
+        MinesweeperVariant::KnightPaths,
+        vec![(1, 2), (2, 1)],
+        vec![(1, 1), (1, 5), (2, 2), (2, 4)],
+        vec![
+            (1, 2),
+            (1, 4),
+            (2, 1),
+            (2, 5),
+            (4, 1),
+            (4, 5),
+            (5, 2),
+            (5, 4),
+        ],
+    );
+}
+
+#[test]
+fn blind_up_neighbors() {
+    check_neighbors(
+        MinesweeperVariant::BlindUp,
+        vec![(0, 1), (1, 0), (1, 1)],
+        vec![(0, 4), (1, 2), (1, 3), (1, 4)],
+        vec![(2, 2), (2, 3), (2, 4), (3, 4), (4, 2), (4, 3), (4, 4)],
+    );
+}
+
+#[test]
+fn blind_down_neighbors() {
+    check_neighbors(
+        MinesweeperVariant::BlindDown,
+        vec![(1, 0), (1, 1)],
+        vec![(0, 2), (1, 2), (1, 3), (1, 4)],
+        vec![(2, 2), (2, 3), (2, 4), (3, 2), (4, 2), (4, 3), (4, 4)],
+    );
+}
+
+#[test]
+fn blind_left_neighbors() {
+    // the blind square is already off the board at the left edge, so nothing else is lost
+    check_neighbors(
+        MinesweeperVariant::BlindLeft,
+        vec![(0, 1), (1, 0), (1, 1)],
+        vec![(0, 2), (0, 4), (1, 2), (1, 3), (1, 4)],
+        vec![(2, 2), (2, 4), (3, 2), (3, 4), (4, 2), (4, 3), (4, 4)],
+    );
+}
+
+#[test]
+fn blind_right_neighbors() {
+    check_neighbors(
+        MinesweeperVariant::BlindRight,
+        vec![(0, 1), (1, 1)],
+        vec![(0, 2), (0, 4), (1, 2), (1, 4)],
+        vec![(2, 2), (2, 3), (2, 4), (3, 2), (3, 4), (4, 2), (4, 4)],
+    );
+}
+
+#[test]
+fn orthogonal_neighbors() {
+    check_neighbors(
+        MinesweeperVariant::Orthogonal,
+        vec![(0, 1), (1, 0)],
+        vec![(0, 2), (0, 4), (1, 3)],
+        vec![(2, 3), (3, 2), (3, 4), (4, 3)],
+    );
+}
+
+#[test]
+fn far_orthogonal_neighbors() {
+    check_neighbors(
+        MinesweeperVariant::FarOrthogonal,
+        vec![(0, 1), (0, 2), (1, 0), (2, 0)],
+        vec![(0, 1), (0, 2), (0, 4), (0, 5), (1, 3), (2, 3)],
+        vec![
+            (1, 3),
+            (2, 3),
+            (3, 1),
+            (3, 2),
+            (3, 4),
+            (3, 5),
+            (4, 3),
+            (5, 3),
+        ],
+    );
+}
+
+#[test]
+fn diagonal_neighbors() {
+    check_neighbors(
+        MinesweeperVariant::Diagonal,
+        vec![(1, 1)],
+        vec![(1, 2), (1, 4)],
+        vec![(2, 2), (2, 4), (4, 2), (4, 4)],
+    );
+}
+
+#[test]
+fn far_diagonal_neighbors() {
+    check_neighbors(
+        MinesweeperVariant::FarDiagonal,
+        vec![(1, 1), (2, 2)],
+        vec![(1, 2), (1, 4), (2, 1), (2, 5)],
+        vec![
+            (1, 1),
+            (1, 5),
+            (2, 2),
+            (2, 4),
+            (4, 2),
+            (4, 4),
+            (5, 1),
+            (5, 5),
+        ],
+    );
+}
+
+#[test]
+fn doubled_neighbors() {
+    // orthogonal neighbors appear twice, so they count for two mines
+    check_neighbors(
+        MinesweeperVariant::Doubled,
+        vec![(0, 1), (0, 1), (1, 0), (1, 0), (1, 1)],
+        vec![
+            (0, 2),
+            (0, 2),
+            (0, 4),
+            (0, 4),
+            (1, 2),
+            (1, 3),
+            (1, 3),
+            (1, 4),
+        ],
+        vec![
+            (2, 2),
+            (2, 3),
+            (2, 3),
+            (2, 4),
+            (3, 2),
+            (3, 2),
+            (3, 4),
+            (3, 4),
+            (4, 2),
+            (4, 3),
+            (4, 3),
+            (4, 4),
+        ],
+    );
+}
+
+#[test]
+fn l_tromino_neighbors() {
+    check_neighbors(
+        MinesweeperVariant::LTromino,
+        vec![(1, 0)],
+        vec![(0, 2), (1, 3)],
+        vec![(3, 2), (4, 3)],
+    );
+}
+
+#[test]
+fn plus_neighbors() {
+    check_neighbors(
+        MinesweeperVariant::Plus,
+        vec![(0, 1), (1, 0)],
+        vec![(0, 2), (0, 4), (1, 3)],
+        vec![(2, 3), (3, 2), (3, 4), (4, 3)],
+    );
+}
+
+#[test]
+fn cross_neighbors() {
+    check_neighbors(
+        MinesweeperVariant::Cross,
+        vec![(1, 1)],
+        vec![(1, 2), (1, 4)],
+        vec![(2, 2), (2, 4), (4, 2), (4, 4)],
+    );
+}
+
+#[test]
+fn ring_neighbors() {
+    check_neighbors(
+        MinesweeperVariant::Ring,
+        vec![(0, 2), (1, 2), (2, 0), (2, 1), (2, 2)],
+        vec![
+            (0, 1),
+            (0, 5),
+            (1, 1),
+            (1, 5),
+            (2, 1),
+            (2, 2),
+            (2, 3),
+            (2, 4),
+            (2, 5),
+        ],
+        area(1..6, 1..6)
+            .into_iter()
+            .filter(|&(x, y)| x.abs_diff(3) == 2 || y.abs_diff(3) == 2)
+            .collect(),
+    );
+}
+
+#[test]
+fn neighbors_clipped_at_far_corner() {
+    // the bottom right corner clips the other side of the board from the corner above
+    let game = board(MinesweeperVariant::KnightPaths);
+    assert_eq!(sorted_neighbors(&game, 6, 6), vec![(4, 5), (5, 4)]);
+    let game = board(MinesweeperVariant::Normal);
+    assert_eq!(sorted_neighbors(&game, 6, 6), vec![(5, 5), (5, 6), (6, 5)]);
+}
+
+#[test]
+fn neighbors_stay_within_reach() {
+    // every variant's neighbors should be on the board and never include far-away squares
+    for variant in MinesweeperVariant::ALL {
+        let game = board(variant);
+        for (x, y) in game.neighbors(3, 3) {
+            assert!(
+                x.abs_diff(3) <= 2 && y.abs_diff(3) <= 2,
+                "{}",
+                variant.name()
+            );
+        }
+    }
+}
+
+#[test]
+fn neighbors_at_extreme_coordinates() {
+    // on a board as big as usize allows, offsets past either end must be dropped, not overflow
+    let mut game = board(MinesweeperVariant::FarNormal);
+    let far = usize::MAX - 1;
+    game.width = usize::MAX;
+    game.height = usize::MAX;
+    game.all_squares
+        .extend(area(far - 2..far + 1, far - 2..far + 1));
+    assert_eq!(sorted_neighbors(&game, 0, 0), area(0..3, 0..3));
+    assert_eq!(
+        sorted_neighbors(&game, far, far),
+        area(far - 2..far + 1, far - 2..far + 1)
+    );
+}
+
+#[test]
+fn last_safe_square_wins_without_determine_win() {
+    // 3x1 board with a mine at the left end: opening the right end cascades to the number
+    let mines = HashSet::from([(0, 0)]);
+    let mut game = Minesweeper::new_with_mines(settings(3, 1, MinesweeperVariant::Normal), mines);
+    assert_eq!(game.open(2, 0), Ok(2));
+    assert!(game.state == GameState::Won);
+}
+
+#[test]
+fn no_mines_wins_on_first_open() {
+    let mut game = board(MinesweeperVariant::Normal);
+    assert_eq!(game.open(3, 3), Ok(49));
+    assert!(game.state == GameState::Won);
+}
+
+#[test]
+fn one_safe_square_wins_when_opened() {
+    let mines = area(0..7, 0..7)
+        .into_iter()
+        .filter(|&square| square != (6, 6))
+        .collect();
+    let mut game = Minesweeper::new_with_mines(settings(7, 7, MinesweeperVariant::Normal), mines);
+    assert_eq!(game.open(6, 6), Ok(1));
+    assert!(game.state == GameState::Won);
+}
+
+#[test]
+fn opening_a_mine_loses() {
+    let mines = HashSet::from([(0, 0)]);
+    let mut game = Minesweeper::new_with_mines(settings(7, 7, MinesweeperVariant::Normal), mines);
+    assert_eq!(game.open(0, 0), Ok(0));
+    assert!(game.state == GameState::Lost);
+}
+
+#[test]
+fn out_of_bounds_moves_are_errors() {
+    let mut game = board(MinesweeperVariant::Normal);
+    assert_eq!(game.open(7, 0), Err(OutOfBounds));
+    assert_eq!(game.open(0, 7), Err(OutOfBounds));
+    assert_eq!(game.flag(7, 7), Err(OutOfBounds));
+    assert!(game.open_squares.is_empty() && game.flagged_squares.is_empty());
+}
+
+#[test]
+fn flags_block_cascade() {
+    // 5x1 board with a mine at the right end: a cascade from the left reaches the flag and stops
+    let mines = HashSet::from([(4, 0)]);
+    let mut game = Minesweeper::new_with_mines(settings(5, 1, MinesweeperVariant::Normal), mines);
+    assert_eq!(game.flag(1, 0), Ok(()));
+    assert_eq!(game.open(0, 0), Ok(1));
+    assert!(game.flagged_squares.contains(&(1, 0)));
+    assert!(game.state == GameState::Playing);
+}
+
+#[test]
+fn cascade_clears_flags() {
+    // the same board, but the cascade removes the wrong flag and carries on until the number
+    let mines = HashSet::from([(4, 0)]);
+    let mut game = Minesweeper::new_with_mines(
+        GameSettings {
+            cascade_clears_flags: true,
+            ..settings(5, 1, MinesweeperVariant::Normal)
+        },
+        mines,
+    );
+    assert_eq!(game.flag(1, 0), Ok(()));
+    assert_eq!(game.open(0, 0), Ok(4));
+    assert!(game.flagged_squares.is_empty());
+    assert!(game.state == GameState::Won);
+}
+
+#[test]
+fn seeded_boards_are_reproducible() {
+    let settings = GameSettings {
+        num_mines: 10,
+        seed: Some(42),
+        ..settings(7, 7, MinesweeperVariant::Normal)
+    };
+    let first = Minesweeper::new(settings.clone());
+    let second = Minesweeper::new(settings);
+    assert_eq!(first.mines, second.mines);
+    assert_eq!(first.mines.len(), 10);
+}
+
+// fn to make a 3x2 board with a mine in the middle of the bottom row and the top row open
+// each number is a 1, so the single-square rule alone can't prove anything
+fn subset_board() -> Minesweeper {
+    let mines = HashSet::from([(1, 1)]);
+    let mut game = Minesweeper::new_with_mines(settings(3, 2, MinesweeperVariant::Normal), mines);
+    for x in 0..3 {
+        assert_eq!(game.open(x, 0), Ok(1));
+    }
+    game
+}
+
+#[test]
+fn determinable_cells_by_technique() {
+    let game = subset_board();
+    let (safe, mines) = game.determinable_cells(Technique::Single);
+    assert!(safe.is_empty() && mines.is_empty());
+    let (safe, mines) = game.determinable_cells(Technique::Subset);
+    assert_eq!(safe, HashSet::from([(0, 1), (2, 1)]));
+    assert_eq!(mines, HashSet::from([(1, 1)]));
+    // nothing is opened to find them
+    assert_eq!(game.open_squares.len(), 3);
+}
+
+#[test]
+fn wrong_flags_dont_stop_solving() {
+    let mut game = subset_board();
+    assert_eq!(game.flag(0, 1), Ok(()));
+    assert_eq!(game.determinable_cells(Technique::Subset).0.len(), 2);
+    assert!(game.is_solvable());
+}
+
+#[test]
+fn pre_open_opens_safe_squares() {
+    for pre_open in [PreOpen::Squares(3), PreOpen::Zero] {
+        let game = Minesweeper::new(GameSettings {
+            num_mines: 10,
+            seed: Some(7),
+            pre_open: Some(pre_open),
+            ..settings(7, 7, MinesweeperVariant::Normal)
+        });
+        assert!(!game.open_squares.is_empty());
+        assert!(game.open_squares.is_disjoint(&game.mines));
+        assert!(game.state == GameState::Playing);
+    }
+}
+
+#[test]
+fn pre_open_never_wins() {
+    // the only safe square would win the game, so nothing is opened
+    let game = Minesweeper::new(GameSettings {
+        num_mines: 1,
+        pre_open: Some(PreOpen::Squares(1)),
+        ..settings(2, 1, MinesweeperVariant::Normal)
+    });
+    assert!(game.open_squares.is_empty());
+    assert!(game.state == GameState::Playing);
+}
+
+#[test]
+fn minimap_summarises_blocks() {
+    // 4x2 board with a mine at the right end, opened from the left: the left 2x2 block is all
+    // open, the right block only has half its squares open
+    let mines = HashSet::from([(3, 0)]);
+    let mut game = Minesweeper::new_with_mines(
+        GameSettings {
+            minimap_scale: 2,
+            ..settings(4, 2, MinesweeperVariant::Normal)
+        },
+        mines,
+    );
+    assert_eq!(game.open(0, 0), Ok(6));
+    assert_eq!(
+        game.format_minimap(),
+        "Minimap (2x2 squares per char):\n+--+\n|.#|\n+--+\n"
+    );
+    game.last_move = Some((3, 1));
+    assert_eq!(
+        game.format_minimap(),
+        "Minimap (2x2 squares per char):\n+--+\n|.@|\n+--+\n"
+    );
+}
+
+#[test]
+fn detective_flags() {
+    let mines = HashSet::from([(0, 0), (1, 0)]);
+    let detective = GameSettings {
+        detective: true,
+        ..settings(3, 1, MinesweeperVariant::Normal)
+    };
+    // a correctly flagged mine shows the mines near it, a wrong flag shows nothing
+    let mut game = Minesweeper::new_with_mines(detective.clone(), mines.clone());
+    assert_eq!(game.flag(0, 0), Ok(()));
+    assert_eq!(game.flag(2, 0), Ok(()));
+    assert!(game.to_string().contains("| F1. F |"));
+    assert!(game.state == GameState::Playing);
+    // unless wrong flags lose the game
+    let mut game = Minesweeper::new_with_mines(
+        GameSettings {
+            wrong_flags_lose: true,
+            ..detective
+        },
+        mines,
+    );
+    assert_eq!(game.flag(2, 0), Ok(()));
+    assert!(game.state == GameState::Lost);
+}
+
+#[test]
+fn detective_flags_line_up() {
+    // flags showing numbers take up as many columns as closed squares, in every theme
+    // (even with 2 digits, or a flag which is already 2 columns wide)
+    for theme in Theme::ALL
+        .into_iter()
+        .flat_map(|theme| [theme, theme.without_colors()])
+    {
+        for number in 0..=24 {
+            assert_eq!(
+                theme.display_width(&theme.flag_number(number)),
+                theme.display_width(theme.closed),
+                "{} {number}",
+                theme.name
+            );
+        }
+    }
+    assert_eq!(Theme::CLASSIC.flag_number(3), "F3");
+    assert_eq!(Theme::CLASSIC.without_colors().flag_number(12), "12");
+}
+
+#[test]
+fn themes() {
+    assert_eq!("Retro".parse::<Theme>().map(|theme| theme.flag), Ok("|>"));
+    assert!("bombs".parse::<Theme>().is_err());
+    // numbers are colored unless the theme has no colors
+    let emoji: Theme = "emoji".parse().unwrap();
+    assert_eq!(emoji.number(3), "\x1b[91m3\x1b[0m ");
+    assert_eq!(emoji.without_colors().number(3), "3 ");
+    assert_eq!(Theme::CLASSIC.number(3), "3 ");
+}
+
+#[test]
+fn palettes() {
+    let colorblind: Palette = "Colorblind".parse().unwrap();
+    assert!("rainbow".parse::<Palette>().is_err());
+    // palettes replace a theme's colors, even if it had none
+    let theme = Theme::CLASSIC.with_palette(colorblind);
+    assert_eq!(theme.number(2), "\x1b[38;5;214m2\x1b[0m ");
+    assert_eq!(theme.flag, Theme::CLASSIC.flag);
+    assert_eq!(theme.without_colors().number(2), "2 ");
+}
+
+#[test]
+fn certain_moves() {
+    // nothing is known before the first open
+    let game = Minesweeper::new_with_mines(
+        settings(3, 2, MinesweeperVariant::Normal),
+        HashSet::from([(1, 1)]),
+    );
+    assert!(!game.has_certain_move());
+    // the subset rule finds a move once the top row is open
+    assert!(subset_board().has_certain_move());
+}
+
+#[test]
+fn lives() {
+    let mines = HashSet::from([(0, 0), (2, 0)]);
+    let mut game = Minesweeper::new_with_mines(
+        GameSettings {
+            lives: Some(2),
+            ..settings(3, 1, MinesweeperVariant::Normal)
+        },
+        mines,
+    );
+    // the first mine hit costs a life and is flagged
+    assert_eq!(game.open(0, 0), Ok(0));
+    assert_eq!(game.lives, Some(1));
+    assert!(game.flagged_squares.contains(&(0, 0)) && game.state == GameState::Playing);
+    // the last life lost loses the game
+    assert_eq!(game.open(2, 0), Ok(0));
+    assert_eq!(game.lives, Some(0));
+    assert!(game.state == GameState::Lost);
+}
+
+#[test]
+fn helps_only_open_safe_squares() {
+    let mines = HashSet::from([(0, 0), (3, 0), (0, 3), (3, 3), (1, 2)]);
+    let mut game = Minesweeper::new_with_mines(
+        GameSettings {
+            helps: Some(2),
+            ..settings(4, 4, MinesweeperVariant::Normal)
+        },
+        mines,
+    );
+    let mut rng = SeededRng::new(7);
+    assert!(game.help(&mut rng) >= HELP_SQUARES);
+    assert_eq!(game.helps, Some(1));
+    assert_eq!(game.moves.len(), HELP_SQUARES);
+    assert!(game.open_squares.is_disjoint(&game.mines));
+    // helps are kept in saves
+    let save = game.save_to_string();
+    let loaded = Minesweeper::load_from_string(&save, settings(4, 4, MinesweeperVariant::Normal));
+    assert_eq!(loaded.unwrap().helps, Some(1));
+    // a help stops once every safe square is open
+    while game.state == GameState::Playing {
+        game.help(&mut rng);
+    }
+    assert!(game.state == GameState::Won);
+}
+
+#[test]
+fn needed_technique() {
+    assert_eq!(subset_board().needed_technique(), Some(Technique::Subset));
+    // opening another square gives a number which makes single-square deductions enough
+    let mut game = subset_board();
+    assert_eq!(game.open(0, 1), Ok(1));
+    assert_eq!(game.needed_technique(), Some(Technique::Single));
+    // nothing is known before the first open, so a guess is needed
+    let game = Minesweeper::new_with_mines(
+        settings(3, 2, MinesweeperVariant::Normal),
+        HashSet::from([(1, 1)]),
+    );
+    assert_eq!(game.needed_technique(), None);
+}
+
+#[test]
+fn generate_board_needing_technique() {
+    for technique in [Technique::Single, Technique::Subset] {
+        let mut game = Minesweeper::new(GameSettings {
+            num_mines: 10,
+            seed: Some(1),
+            no_guessing: true,
+            required_technique: Some(technique),
+            ..settings(9, 9, MinesweeperVariant::Normal)
+        });
+        assert_eq!(game.open(4, 4).map(|opened| opened > 0), Ok(true));
+        assert_eq!(game.needed_technique(), Some(technique));
+    }
+}
+
+#[test]
+fn html() {
+    let mines = HashSet::from([(3, 0)]);
+    let mut game = Minesweeper::new_with_mines(settings(4, 1, MinesweeperVariant::Normal), mines);
+    assert_eq!(game.flag(3, 0), Ok(()));
+    assert_eq!(game.open(1, 0), Ok(3));
+    assert_eq!(
+        game.to_html(),
+        "<table class=\"minesweeper\">\n<caption>0 mines left</caption>\n<tr>\
+         <td class=\"open\"></td><td class=\"open\"></td><td class=\"open n1\">1</td>\
+         <td class=\"flag\"></td></tr>\n</table>\n"
+    );
+}
+
+// fn to make a 4x3 blind up board with some mines, open squares and flags, for transforming
+fn transform_board() -> Minesweeper {
+    let mines = HashSet::from([(0, 0), (3, 1)]);
+    let mut game = Minesweeper::new_with_mines(settings(4, 3, MinesweeperVariant::BlindUp), mines);
+    assert_eq!(game.open(0, 2), Ok(7));
+    assert_eq!(game.flag(3, 1), Ok(()));
+    game
+}
+
+// fn to get everything a transform changes about a board, to compare boards
+fn board_state(game: &Minesweeper) -> (usize, usize, Vec<Vec<Position>>, MinesweeperVariant) {
+    let sets = [
+        &game.mines,
+        &game.open_squares,
+        &game.flagged_squares,
+        &game.all_squares,
+    ];
+    let sets = sets
+        .iter()
+        .map(|set| sorted(set.iter().copied().collect()))
+        .collect();
+    (game.width, game.height, sets, game.variant)
+}
+
+#[test]
+fn double_flips_are_identity() {
+    let original = board_state(&transform_board());
+    let mut game = transform_board();
+    game.flip_horizontal();
+    assert!(board_state(&game) != original);
+    game.flip_horizontal();
+    assert!(board_state(&game) == original);
+    game.flip_vertical();
+    assert!(board_state(&game) != original);
+    game.flip_vertical();
+    assert!(board_state(&game) == original);
+}
+
+#[test]
+fn four_rotations_are_identity() {
+    let original = board_state(&transform_board());
+    let mut game = transform_board();
+    for _ in 0..4 {
+        game.rotate_90();
+    }
+    assert!(board_state(&game) == original);
+}
+
+#[test]
+fn rotation_remaps_squares() {
+    let mut game = transform_board();
+    game.rotate_90();
+    // the 4x3 board becomes 3x4, with the top left corner now at the top right
+    assert_eq!((game.width, game.height), (3, 4));
+    assert_eq!(
+        sorted(game.mines.iter().copied().collect()),
+        vec![(1, 3), (2, 0)]
+    );
+    assert!(game.open_squares.contains(&(0, 0)) && game.flagged_squares.contains(&(1, 3)));
+    // the blind square turns with the board, so numbers stay the same
+    assert!(game.variant == MinesweeperVariant::BlindRight);
+    assert_eq!(game.mines_near(0, 0), transform_board().mines_near(0, 2));
+}
+
+// fn to load a 3x1 normal board with a mine on the left, from its saved state and open squares
+fn load_board(state: &str, open: &str) -> Result<Minesweeper, LoadError> {
+    let save = format!("size 3 1\nvariant normal\nstate {state}\nmines 0,0\nopen{open}\nflagged\n");
+    Minesweeper::load_from_string(&save, settings(3, 1, MinesweeperVariant::Normal))
+}
+
+#[test]
+fn loading_reconciles_state() {
+    // an opened mine loses the game
+    assert!(load_board("playing", " 0,0 1,0").unwrap().state == GameState::Lost);
+    // every safe square open wins the game
+    assert!(load_board("playing", " 1,0 2,0").unwrap().state == GameState::Won);
+    // a game can't be won with safe squares closed
+    assert!(load_board("won", " 2,0").unwrap().state == GameState::Playing);
+    // a game which was lost stays lost, as the mine hit isn't opened
+    assert!(load_board("lost", " 2,0").unwrap().state == GameState::Lost);
+    assert!(load_board("playing", " 2,0").unwrap().state == GameState::Playing);
+}
+
+#[test]
+fn loading_reconciles_state_by_win_condition() {
+    let save = "size 3 1\nvariant normal\nstate playing\nmines 0,0\nopen 2,0\nflagged\n";
+    // a custom win condition decides if a loaded game is won, as it does while playing
+    let target: WinCondition = Arc::new(|game| game.open_squares.contains(&(2, 0)));
+    let settings = settings(3, 1, MinesweeperVariant::Normal);
+    let game = Minesweeper::load_from_string(
+        save,
+        GameSettings {
+            win_condition: Some(target),
+            ..settings.clone()
+        },
+    );
+    assert!(game.unwrap().state == GameState::Won);
+    // and a game needing flags to win isn't won without them, even with every safe square open
+    let save = save.replace("open 2,0", "open 1,0 2,0");
+    let game = Minesweeper::load_from_string(
+        &save,
+        GameSettings {
+            flag_to_win: true,
+            ..settings
+        },
+    );
+    assert!(game.unwrap().state == GameState::Playing);
+}
+
+#[test]
+fn loading_rejects_inconsistent_squares() {
+    let save = "size 3 1\nvariant normal\nstate playing\nmines 0,0\nopen 2,0\nflagged 2,0\n";
+    assert!(matches!(
+        Minesweeper::load_from_string(save, settings(3, 1, MinesweeperVariant::Normal)),
+        Err(LoadError::Inconsistent(squares)) if squares == vec![(2, 0)]
+    ));
+}
+
+// chars random inputs are made from: the digits, separators and words the parsers look for,
+// and some they don't (including a multi-byte char, to catch strings being split mid-char)
+const FUZZ_CHARS: [char; 16] = [
+    '0', '1', '2', '9', ' ', ',', '\n', '\t', '-', '+', 'q', 'A', 'z', 'é', '.', '#',
+];
+
+// number of random inputs each parser is given
+const FUZZ_CASES: usize = 2000;
+
+// fn to make a random string of up to 12 chars
+fn random_input(rng: &mut SeededRng) -> String {
+    let len = rng.gen_index(13);
+    (0..len)
+        .map(|_| FUZZ_CHARS[rng.gen_index(FUZZ_CHARS.len())])
+        .collect()
+}
+
+// fn to change up to 3 chars of a string at random (inserting, replacing or removing them),
+// so that inputs are usually close enough to valid to get past the first checks
+fn mutate(rng: &mut SeededRng, valid: &str) -> String {
+    let mut chars: Vec<char> = valid.chars().collect();
+    for _ in 0..=rng.gen_index(3) {
+        let i = rng.gen_index(chars.len() + 1);
+        let c = FUZZ_CHARS[rng.gen_index(FUZZ_CHARS.len())];
+        match rng.gen_index(3) {
+            0 => chars.insert(i, c),
+            _ if i == chars.len() => {}
+            1 => chars[i] = c,
+            _ => {
+                chars.remove(i);
+            }
+        }
+    }
+    chars.into_iter().collect()
+}
+
+#[test]
+fn move_parsers_never_panic() {
+    let mut rng = SeededRng::new(110);
+    let game = Minesweeper::new_with_mines(
+        GameSettings {
+            show_letters: true,
+            ..settings(7, 7, MinesweeperVariant::Normal)
+        },
+        HashSet::new(),
+    );
+    for _ in 0..FUZZ_CASES {
+        let raw = random_input(&mut rng);
+        // anything parsed must be on the board
+        if let Ok(Some(x)) = Minesweeper::validate_move_pos(&raw, 7) {
+            assert!(x < 7, "{raw:?}");
+        }
+        if let Ok(positions) = game.validate_move_list(&raw) {
+            assert!(
+                positions.iter().all(|pos| game.all_squares.contains(pos)),
+                "{raw:?}"
+            );
+        }
+        if let Some(pos) = game.parse_square_name(&raw) {
+            assert!(game.all_squares.contains(&pos), "{raw:?}");
+        }
+        if let Ok((width, height)) = Minesweeper::validate_size(&raw, &random_input(&mut rng)) {
+            assert!(
+                width.checked_mul(height).is_some_and(|cells| cells > 0),
+                "{raw:?}"
+            );
+        }
+        // and actions parse back from how they are displayed
+        if let Ok(action) = raw.parse::<Action>() {
+            assert_eq!(action.to_string().parse(), Ok(action), "{raw:?}");
+        }
+    }
+}
+
+#[test]
+fn sizes_whose_squares_overflow_are_rejected() {
+    // this used to overflow working out the new mine count when resizing
+    assert_eq!(
+        Minesweeper::validate_size("4294967296", "4294967296"),
+        Err(SizeValidationError)
+    );
+}
+
+#[test]
+fn loaders_never_panic() {
+    let mut rng = SeededRng::new(110);
+    let save = "size 3 1\nvariant normal\nstate playing\nmines 0,0\nopen 2,0\nflagged\n";
+    for _ in 0..FUZZ_CASES {
+        let raw = mutate(&mut rng, save);
+        // a loaded game only has squares on its board
+        if let Ok(game) =
+            Minesweeper::load_from_string(&raw, settings(3, 1, MinesweeperVariant::Normal))
+        {
+            let mut squares = game.mines.iter().chain(&game.open_squares);
+            assert!(
+                squares.all(|&(x, y)| x < game.width && y < game.height),
+                "{raw:?}"
+            );
+        }
+    }
+    // replays are only checked once parsed, so parsing just has to return
+    let replay = "seed 1\noutcome won\nmoves open 2,0 flag 0,0\n";
+    for _ in 0..FUZZ_CASES {
+        let _ = Minesweeper::parse_replay(&mutate(&mut rng, replay));
+    }
+    for _ in 0..FUZZ_CASES {
+        let mbf: Vec<u8> = (0..rng.gen_index(9))
+            .map(|_| [0, 1, 2, 3, 255][rng.gen_index(5)])
+            .collect();
+        if let Ok(game) = Minesweeper::from_mbf(&mbf, settings(1, 1, MinesweeperVariant::Normal)) {
+            assert_eq!(
+                game.mines.len(),
+                usize::from(u16::from_be_bytes([mbf[2], mbf[3]]))
+            );
+        }
+    }
+}
+
+#[test]
+fn playing_moves_without_io() {
+    let mines = HashSet::from([(0, 0)]);
+    let mut game = Minesweeper::new_with_mines(settings(3, 1, MinesweeperVariant::Normal), mines);
+    let moves = [
+        (MoveType::Flag, (0, 0)),
+        (MoveType::Open, (5, 5)),
+        (MoveType::Open, (1, 0)),
+        (MoveType::Open, (2, 0)),
+        (MoveType::Open, (0, 0)),
+    ];
+    assert!(game.play_moves(&moves) == GameState::Won);
+    // the move off the board and the move after winning did nothing
+    assert_eq!(game.moves.len(), 3);
+    assert!(game.play_moves(&[(MoveType::Surrender, (0, 0))]) == GameState::Won);
+}
+
+#[test]
+fn playing_moves_stops_when_lost() {
+    let mines = HashSet::from([(0, 0)]);
+    let mut game = Minesweeper::new_with_mines(settings(3, 1, MinesweeperVariant::Normal), mines);
+    let moves = [(MoveType::Surrender, (1, 0)), (MoveType::Open, (2, 0))];
+    assert!(game.play_moves(&moves) == GameState::Lost);
+    assert!(game.open_squares.is_empty());
+}
+
+#[test]
+fn nearest_mine_distance_is_chebyshev() {
+    assert_eq!(
+        board(MinesweeperVariant::Normal).nearest_mine_distance(0, 0),
+        None
+    );
+    let mines = HashSet::from([(1, 1), (6, 6)]);
+    let game = Minesweeper::new_with_mines(settings(7, 7, MinesweeperVariant::KnightPaths), mines);
+    assert_eq!(game.nearest_mine_distance(1, 1), Some(0));
+    assert_eq!(game.nearest_mine_distance(0, 0), Some(1));
+    assert_eq!(game.nearest_mine_distance(4, 1), Some(3));
+    assert_eq!(game.nearest_mine_distance(5, 3), Some(3));
+}
+
+#[test]
+fn registered_variants_parse_and_count_mines() {
+    let variant =
+        MinesweeperVariant::register("test-wide", "up to 2 squares left or right", || {
+            vec![(-2, 0), (-1, 0), (1, 0), (2, 0)]
+        })
+        .unwrap();
+    assert!("Test-Wide".parse::<MinesweeperVariant>() == Ok(variant));
+    assert_eq!(variant.name(), "test-wide");
+    assert!(MinesweeperVariant::list().contains("test-wide: up to 2 squares left or right"));
+    let game = board(variant);
+    assert_eq!(sorted_neighbors(&game, 1, 3), vec![(0, 3), (2, 3), (3, 3)]);
+    // names can't be taken twice, or from a built-in variant or alias
+    for name in ["test-wide", "normal", "cross"] {
+        assert!(MinesweeperVariant::register(name, "", Vec::new).is_err());
+    }
+}
+
+#[test]
+fn racing_registrations_take_a_name_once() {
+    let threads: Vec<_> = (0..8)
+        .map(|_| {
+            std::thread::spawn(|| MinesweeperVariant::register("test-race", "", Vec::new).is_ok())
+        })
+        .collect();
+    let registered = threads
+        .into_iter()
+        .map(|thread| thread.join().unwrap())
+        .filter(|&ok| ok)
+        .count();
+    assert_eq!(registered, 1);
+}
+
+#[test]
+fn custom_variants_parse_from_cmd_line() {
+    assert_eq!(
+        parse_custom_variant("Wide:-2,0; 2,0".to_string()),
+        Ok(("wide".to_string(), vec![(-2, 0), (2, 0)]))
+    );
+    assert!(parse_custom_variant("wide".to_string()).is_err());
+    assert!(parse_custom_variant("wide:1".to_string()).is_err());
+}
+
+#[test]
+fn status_line_counts_mines_flags_and_opens() {
+    let mut game = subset_board();
+    assert!(!game.to_string().contains("Mines:"));
+    game.show_status = true;
+    assert!(game.flag(1, 1).is_ok());
+    let status = game.to_string();
+    assert!(status.starts_with("Mines: 1 | Flags: 1 | Opened: 3 | Time: "));
+    // the time is counted from the first open
+    assert!(Minesweeper::new_with_mines(
+        settings(3, 2, MinesweeperVariant::Normal),
+        HashSet::new()
+    )
+    .format_status()
+    .ends_with("Time: 0s"));
+}
+
+#[test]
+fn column_names_continue_past_z() {
+    let names = [
+        (0, "A"),
+        (25, "Z"),
+        (26, "AA"),
+        (27, "AB"),
+        (51, "AZ"),
+        (52, "BA"),
+    ];
+    for (x, name) in names.into_iter().chain([(701, "ZZ"), (702, "AAA")]) {
+        assert_eq!(column_name(x), name);
+        assert_eq!(parse_column_name(name), Some(x));
+    }
+    assert_eq!(parse_column_name("ab"), Some(27));
+    for raw in ["", "A1", "-", &"Z".repeat(20)] {
+        assert_eq!(parse_column_name(raw), None);
+    }
+}
+
+#[test]
+fn squares_named_with_letters() {
+    let mut game =
+        Minesweeper::new_with_mines(settings(28, 2, MinesweeperVariant::Normal), HashSet::new());
+    // squares can only be named when cols are named with letters
+    assert_eq!(game.parse_square_name("A1"), None);
+    game.show_letters = true;
+    assert_eq!(game.parse_square_name("A1"), Some((0, 0)));
+    assert_eq!(game.parse_square_name("ab2"), Some((27, 1)));
+    for raw in ["AC1", "A3", "A0", "A", "1", "q"] {
+        assert_eq!(game.parse_square_name(raw), None);
+    }
+    // names longer than one letter are written vertically above their col
+    let header: Vec<String> = game
+        .to_string()
+        .lines()
+        .take(2)
+        .map(str::to_owned)
+        .collect();
+    assert_eq!(header[0], format!("  {}A A", " ".repeat(52)));
+    assert!(header[1].starts_with("  A B C") && header[1].ends_with("Y Z A B"));
+}
+
+#[test]
+fn move_history_export() {
+    let mines = HashSet::from([(0, 0)]);
+    let mut game = Minesweeper::new_with_mines(settings(3, 1, MinesweeperVariant::Normal), mines);
+    let moves = [
+        (MoveType::Flag, (0, 0)),
+        (MoveType::Open, (1, 0)),
+        (MoveType::Open, (2, 0)),
+    ];
+    assert!(game.play_moves(&moves) == GameState::Won);
+    let history = game.export_history();
+    let lines: Vec<Vec<&str>> = history
+        .lines()
+        .map(|line| line.split(' ').collect())
+        .collect();
+    // moves before the first open are at time 0
+    assert_eq!(lines[0], vec!["0", "flag", "0,0"]);
+    assert_eq!(lines[1][1..], ["open", "1,0"]);
+    assert_eq!(lines[2][1..], ["open", "2,0"]);
+    assert!(lines[1..]
+        .iter()
+        .all(|line| line[0].parse::<u128>().is_ok()));
+}
+
+#[test]
+fn spaced_mines() {
+    let squares = Minesweeper::generate_all_squares(9, 9);
+    let mut rng = SeededRng::new(3);
+    let mines = Minesweeper::generate_spaced_mines(&squares, 9, 3, &mut rng).unwrap();
+    assert_eq!(mines.len(), 9);
+    for &a in &mines {
+        assert!(mines
+            .iter()
+            .all(|&b| a == b || chebyshev_distance(a, b) >= 3));
+    }
+    // at most 4 mines fit 3 apart on a 4x4 board
+    let squares = Minesweeper::generate_all_squares(4, 4);
+    assert!(Minesweeper::generate_spaced_mines(&squares, 5, 3, &mut rng).is_none());
+    // games fall back to unspaced mines when they can't be spaced
+    let game = Minesweeper::new(GameSettings {
+        num_mines: 5,
+        min_mine_spacing: Some(3),
+        seed: Some(1),
+        ..settings(4, 4, MinesweeperVariant::Normal)
+    });
+    assert_eq!(game.mines.len(), 5);
+}
+
+#[test]
+fn mines_stay_spaced_when_moved_or_regenerated() {
+    let spaced_apart = |game: &Minesweeper| {
+        game.mines.iter().all(|&a| {
+            game.mines
+                .iter()
+                .all(|&b| a == b || chebyshev_distance(a, b) >= 2)
+        })
+    };
+    for seed in 0..10 {
+        let new_game = |safe_radius, no_guessing| {
+            Minesweeper::new(GameSettings {
+                num_mines: 10,
+                min_mine_spacing: Some(2),
+                safe_radius,
+                no_guessing,
+                seed: Some(seed),
+                ..settings(9, 9, MinesweeperVariant::Normal)
+            })
+        };
+        for (safe_radius, no_guessing) in [(Some(1), false), (None, true)] {
+            let mut game = new_game(safe_radius, no_guessing);
+            assert!(game.open(4, 4).is_ok());
+            assert_eq!(game.mines.len(), 10);
+            assert!(spaced_apart(&game));
+        }
+        let mut game = new_game(None, false);
+        game.resize(12, 12);
+        assert!(spaced_apart(&game));
+    }
+}
+
+#[test]
+fn pausing_hides_the_board() {
+    let mut game = subset_board();
+    assert!(game.flag(1, 1).is_ok());
+    let board = game.to_string();
+    game.toggle_pause();
+    let paused = game.to_string();
+    assert!(paused.starts_with("Paused\n"));
+    assert!(!paused.contains('1') && !paused.contains('F'));
+    game.toggle_pause();
+    assert_eq!(game.to_string(), board);
+}
+
+#[test]
+fn pausing_can_stop_the_timer() {
+    let now = Instant::now();
+    let mut game = subset_board();
+    game.started = Some(now - Duration::from_secs(10));
+    game.paused = Some(now - Duration::from_secs(4));
+    // the timer keeps running while paused by default
+    assert_eq!(game.elapsed().as_secs(), 10);
+    game.pause_stops_timer = true;
+    assert_eq!(game.elapsed().as_secs(), 6);
+    // after resuming, the time paused is left out
+    game.toggle_pause();
+    assert_eq!(game.elapsed().as_secs(), 6);
+}
+
+#[test]
+fn cell_iterators_cover_the_board() {
+    let mut game = subset_board();
+    assert!(game.flag(1, 1).is_ok());
+    let open: HashMap<Position, usize> = game.open_cells().collect();
+    assert_eq!(open, HashMap::from([((0, 0), 1), ((1, 0), 1), ((2, 0), 1)]));
+    assert_eq!(game.flagged_cells().collect::<Vec<_>>(), vec![(1, 1)]);
+    assert_eq!(
+        game.closed_cells().collect::<HashSet<_>>(),
+        HashSet::from([(0, 1), (2, 1)])
+    );
+}
+
+#[test]
+fn flagging_needed_to_win() {
+    let mines = HashSet::from([(0, 0), (2, 0)]);
+    let mut game = Minesweeper::new_with_mines(
+        GameSettings {
+            flag_to_win: true,
+            ..settings(3, 2, MinesweeperVariant::Normal)
+        },
+        mines,
+    );
+    assert!(game.flag(0, 0).is_ok());
+    for x in 0..3 {
+        let _ = game.open(x, 1);
+    }
+    assert!(game.open(1, 0).is_ok());
+    // every safe square is open, but a mine is still unflagged
+    assert!(game.only_flags_left());
+    assert!(game.flag(2, 0).is_ok());
+    assert!(game.state == GameState::Won && !game.only_flags_left());
+    // without it, opening every safe square wins straight away
+    let mines = HashSet::from([(0, 0), (2, 0)]);
+    let mut game = Minesweeper::new_with_mines(settings(3, 1, MinesweeperVariant::Normal), mines);
+    assert_eq!(game.open(1, 0), Ok(1));
+    assert!(game.state == GameState::Won);
+}
+
+#[test]
+fn replaying_to_a_move() {
+    let settings = || GameSettings {
+        num_mines: 3,
+        ..settings(5, 5, MinesweeperVariant::Normal)
+    };
+    let mut game = Minesweeper::new(GameSettings {
+        seed: Some(9),
+        ..settings()
+    });
+    let mut safe: Vec<Position> = game.all_squares.difference(&game.mines).copied().collect();
+    safe.sort_unstable();
+    let moves: Vec<Move> = safe.iter().map(|&pos| (MoveType::Open, pos)).collect();
+    game.play_moves(&moves[..1]);
+    let after_one = game.open_squares.clone();
+    game.play_moves(&moves[1..]);
+    // rewinding to the first move gives the board as it was then, which can be played on
+    let mut rewound = Minesweeper::replay_to(settings(), 9, &game.moves, 1);
+    assert_eq!(rewound.open_squares, after_one);
+    assert!(rewound.state == GameState::Playing && rewound.moves.len() == 1);
+    assert!(rewound.play_moves(&moves) == GameState::Won);
+    // rewinding past the end replays every move
+    assert_eq!(
+        Minesweeper::replay_to(settings(), 9, &game.moves, 100).open_squares,
+        game.open_squares
+    );
+}
+
+#[test]
+fn unseeded_games_can_be_verified() {
+    let settings = || GameSettings {
+        num_mines: 3,
+        ..settings(5, 5, MinesweeperVariant::Normal)
+    };
+    // a game without a seed setting still records the seed its board was generated from
+    let mut game = Minesweeper::new(settings());
+    let mut safe: Vec<Position> = game.all_squares.difference(&game.mines).copied().collect();
+    safe.sort_unstable();
+    let moves: Vec<Move> = safe.iter().map(|&pos| (MoveType::Open, pos)).collect();
+    assert!(game.play_moves(&moves) == GameState::Won);
+    let (seed, moves, outcome) = Minesweeper::parse_replay(&game.format_replay()).unwrap();
+    assert!(Minesweeper::verify_replay(
+        settings(),
+        seed,
+        &moves,
+        &outcome
+    ));
+}
+
+#[test]
+fn flag_progress() {
+    let mines = HashSet::from([(0, 0), (2, 0)]);
+    let mut game = Minesweeper::new_with_mines(settings(3, 2, MinesweeperVariant::Normal), mines);
+    assert_eq!(
+        game.flag_progress(),
+        FlagProgress {
+            correct: 0,
+            incorrect: Vec::new(),
+            remaining: 2
+        }
+    );
+    // mis-flagged: a safe square flagged instead of a mine
+    assert!(game.flag(0, 0).is_ok() && game.flag(1, 1).is_ok());
+    assert_eq!(
+        game.flag_progress(),
+        FlagProgress {
+            correct: 1,
+            incorrect: vec![(1, 1)],
+            remaining: 1
+        }
+    );
+    // over-flagged: every mine flagged, plus safe squares
+    assert!(game.flag(2, 0).is_ok() && game.flag(0, 1).is_ok());
+    assert_eq!(
+        game.flag_progress(),
+        FlagProgress {
+            correct: 2,
+            incorrect: vec![(0, 1), (1, 1)],
+            remaining: 0
+        }
+    );
+}
+
+#[test]
+fn split_variant() {
+    let mut game = Minesweeper::new_with_mines(
+        GameSettings {
+            split_variant: Some(MinesweeperVariant::KnightPaths),
+            ..settings(6, 5, MinesweeperVariant::Normal)
+        },
+        HashSet::new(),
+    );
+    // the left half counts the normal neighbors, the right half knight moves
+    assert_eq!(game.neighbors(1, 2).len(), 8);
+    assert!(sorted_neighbors(&game, 4, 2).contains(&(2, 1)));
+    assert_eq!(game.variant_name(), "normal:knight-paths");
+    let loaded = Minesweeper::load_from_string(
+        &game.save_to_string(),
+        settings(6, 5, MinesweeperVariant::Normal),
+    );
+    assert_eq!(
+        loaded.map(|loaded| loaded.variant_name()),
+        Ok("normal:knight-paths".to_string())
+    );
+    // flipping the board left to right swaps the halves
+    game.flip_vertical();
+    assert_eq!(game.variant_name(), "knight-paths:normal");
+}
+
+#[test]
+fn quit_game_saved_as_playing() {
+    let mut game = Minesweeper::new_with_mines(
+        settings(3, 1, MinesweeperVariant::Normal),
+        HashSet::from([(0, 0)]),
+    );
+    game.state = GameState::Quit;
+    // a quit game is resumed as still being played
+    let loaded = Minesweeper::load_from_string(
+        &game.save_to_string(),
+        settings(3, 1, MinesweeperVariant::Normal),
+    );
+    assert!(loaded.is_ok_and(|loaded| loaded.state() == GameState::Playing));
+}
+
+// fn to make the densest board allowed, with every square but one a mine
+// mines are placed by the seeded rng, so the same seed always gives the same board
+fn dense_board(variant: MinesweeperVariant, seed: u64) -> Minesweeper {
+    let settings = GameSettings {
+        num_mines: max_mine_count(7 * 7),
+        ..settings(7, 7, variant)
+    };
+    Minesweeper::new_with_seeded_rng(settings, &mut SeededRng::new(seed))
+}
+
+#[test]
+fn dense_board_won_by_its_one_safe_square() {
+    for variant in MinesweeperVariant::all() {
+        for seed in 0..5 {
+            let mut game = dense_board(variant, seed);
+            assert_eq!(game.mine_count(), 7 * 7 - 1);
+            let safe: Vec<Position> = game
+                .closed_cells()
+                .filter(|pos| !game.mines.contains(pos))
+                .collect();
+            assert_eq!(safe.len(), 1);
+            // the safe square is surrounded by mines, so opening it doesn't cascade
+            let (x, y) = safe[0];
+            assert_eq!(game.open(x, y), Ok(1));
+            assert!(game.state == GameState::Won);
+        }
+    }
+}
+
+#[test]
+fn mbf_round_trip() {
+    let mines = HashSet::from([(0, 0), (4, 1), (2, 2)]);
+    let game = Minesweeper::new_with_mines(settings(5, 3, MinesweeperVariant::Normal), mines);
+    let mbf = game.to_mbf().unwrap();
+    assert_eq!(mbf, [5, 3, 0, 3, 0, 0, 2, 2, 4, 1]);
+    let loaded = Minesweeper::from_mbf(&mbf, settings(1, 1, MinesweeperVariant::Normal));
+    assert!(loaded.is_ok_and(|loaded| loaded.width() == 5 && loaded.mines == game.mines));
+    // mines outside the board or missing are rejected
+    for mbf in [&[5, 3, 0, 1, 5, 0][..], &[5, 3, 0, 2, 0, 0], &[5, 3]] {
+        let loaded = Minesweeper::from_mbf(mbf, settings(1, 1, MinesweeperVariant::Normal));
+        assert!(loaded.is_err());
+    }
+}
+
+#[test]
+fn boards_too_big_for_mbf_are_rejected() {
+    let mut game = Minesweeper::new(settings(MBF_MAX_SIZE, 2, MinesweeperVariant::Normal));
+    assert!(game.to_mbf().is_ok());
+    // resizing in game can make the board too big to write
+    game.resize(MBF_MAX_SIZE + 1, 2);
+    assert_eq!(game.to_mbf(), Err(MbfSizeError));
+}
+
+#[test]
+fn unreachable_safe_cells() {
+    // the corners' only diagonal neighbor is the mine, so no cascade can reach them
+    let mines = HashSet::from([(1, 1)]);
+    let game = Minesweeper::new_with_mines(settings(3, 3, MinesweeperVariant::Diagonal), mines);
+    assert_eq!(
+        game.unreachable_safe_cells(),
+        HashSet::from([(0, 0), (2, 0), (0, 2), (2, 2)])
+    );
+    // in the normal variant, every square of an empty board can be reached
+    assert!(board(MinesweeperVariant::Normal)
+        .unreachable_safe_cells()
+        .is_empty());
+}
+
+#[test]
+fn guess_strategies() {
+    let mut game =
+        Minesweeper::new_with_mines(settings(3, 3, MinesweeperVariant::Normal), HashSet::new());
+    // every square is equally safe, and flagged squares aren't guessed
+    assert!(game.flag(0, 0).is_ok());
+    let guess = |strategy| game.best_guess(strategy).map(|(pos, _)| pos);
+    assert_eq!(guess(GuessStrategy::Position), Some((1, 0)));
+    assert_eq!(guess(GuessStrategy::Corner), Some((2, 0)));
+    // the middle square has the most neighbors to cascade into
+    assert_eq!(guess(GuessStrategy::Information), Some((1, 1)));
+    assert_eq!("Corner".parse(), Ok(GuessStrategy::Corner));
+}
+
+#[test]
+fn frontier_partition() {
+    let mines = HashSet::from([(3, 0), (4, 0)]);
+    let mut game = Minesweeper::new_with_mines(settings(5, 1, MinesweeperVariant::Normal), mines);
+    assert_eq!(game.open(0, 0), Ok(3));
+    assert_eq!(
+        game.frontier_partition(),
+        solver::FrontierPartition {
+            frontier: HashSet::from([(3, 0)]),
+            interior: HashSet::from([(4, 0)]),
+            frontier_mines: 1,
+            interior_mines: 1
+        }
+    );
+    // flagged mines aren't counted
+    assert!(game.flag(4, 0).is_ok());
+    assert_eq!(game.frontier_partition().interior_mines, 0);
+}
+
+#[test]
+fn explore_after_win() {
+    let mines = HashSet::from([(2, 0)]);
+    let mut game = Minesweeper::new_with_mines(settings(3, 1, MinesweeperVariant::Normal), mines);
+    assert_eq!(game.open(0, 0), Ok(2));
+    assert!(game.state == GameState::Won);
+    assert!(game.square_view(2, 0) == SquareView::Closed);
+    // opening a mine after winning reveals it, rather than losing
+    assert_eq!(game.open(2, 0), Ok(0));
+    assert!(game.state == GameState::Won);
+    assert!(game.square_view(2, 0) == SquareView::Mine);
+}
+
+#[test]
+fn infer_mines_from_numbers() {
+    let game =
+        Minesweeper::new_with_mines(settings(3, 1, MinesweeperVariant::Normal), HashSet::new());
+    // one mine between two 1s explains both, rather than one next to each
+    let numbers = HashMap::from([((0, 0), 1), ((2, 0), 1)]);
+    assert_eq!(
+        game.infer_mines_from_numbers(&numbers),
+        Some(HashSet::from([(1, 0)]))
+    );
+    // a corner can't have 4 mines near
+    let numbers = HashMap::from([((0, 0), 4)]);
+    assert_eq!(game.infer_mines_from_numbers(&numbers), None);
+}
+
+#[test]
+fn load_from_clues() {
+    let game = Minesweeper::from_clues("1..\n11.\n", settings(1, 1, MinesweeperVariant::Normal));
+    let game = game.unwrap();
+    assert_eq!((game.width(), game.height()), (3, 2));
+    assert_eq!(game.mines, HashSet::from([(1, 0)]));
+    assert!(game.square_view(0, 1) == SquareView::Open(1));
+    // rows must all be the same width
+    let ragged = Minesweeper::from_clues("1..\n1\n", settings(1, 1, MinesweeperVariant::Normal));
+    assert!(ragged.is_err());
+}
+
+#[test]
+fn degenerate_board_sizes() {
+    // a board needs a square in each direction, and can't have more squares than fit in a usize
+    for (width, height) in [(0, 5), (5, 0), (0, 0), (usize::MAX, 2)] {
+        assert_eq!(
+            Minesweeper::count_squares(width, height),
+            Err(SizeValidationError)
+        );
+    }
+    assert_eq!(Minesweeper::count_squares(1, 1), Ok(1));
+    assert_eq!(max_mine_count(1), 0);
+    // a 1x1 board without mines is won by its first open
+    let mut game =
+        Minesweeper::new_with_mines(settings(1, 1, MinesweeperVariant::Normal), HashSet::new());
+    assert_eq!(game.open(0, 0), Ok(1));
+    assert!(game.state == GameState::Won);
+}
+
+#[test]
+fn change_variant() {
+    let mines = HashSet::from([(1, 0)]);
+    let mut game = Minesweeper::new_with_mines(
+        GameSettings {
+            split_variant: Some(MinesweeperVariant::KnightPaths),
+            ..settings(3, 3, MinesweeperVariant::Normal)
+        },
+        mines.clone(),
+    );
+    assert_eq!(game.mines_near(1, 1), 1);
+    // the mines stay put, but are counted by the new variant (on both halves)
+    game.change_variant(MinesweeperVariant::Diagonal);
+    assert_eq!(game.mines, mines);
+    assert_eq!(game.mines_near(1, 1), 0);
+    assert_eq!(game.variant_name(), "diagonal");
+}
+
+#[test]
+fn no_edge_mines() {
+    let settings = GameSettings {
+        num_mines: 9,
+        no_edge_mines: true,
+        ..settings(5, 5, MinesweeperVariant::Normal)
+    };
+    // the 9 mines fill the middle of the board, leaving the edges clear
+    let game = Minesweeper::new_with_seeded_rng(settings.clone(), &mut SeededRng::new(1));
+    assert!(game
+        .mines
+        .iter()
+        .all(|&(x, y)| (1..4).contains(&x) && (1..4).contains(&y)));
+    // if the middle can't hold the mines, the edges are used too
+    let game = Minesweeper::new_with_seeded_rng(
+        GameSettings {
+            num_mines: 10,
+            ..settings
+        },
+        &mut SeededRng::new(1),
+    );
+    assert_eq!(game.mine_count(), 10);
+}
+
+#[test]
+fn edge_mines_stay_clear_when_moved_or_regenerated() {
+    let off_edges = |game: &Minesweeper| {
+        game.mines
+            .iter()
+            .all(|&(x, y)| (1..8).contains(&x) && (1..8).contains(&y))
+    };
+    for seed in 0..10 {
+        let new_game = |safe_radius, no_guessing| {
+            Minesweeper::new(GameSettings {
+                num_mines: 10,
+                no_edge_mines: true,
+                safe_radius,
+                no_guessing,
+                seed: Some(seed),
+                ..settings(9, 9, MinesweeperVariant::Normal)
+            })
+        };
+        for (safe_radius, no_guessing) in [(Some(2), false), (None, true)] {
+            let mut game = new_game(safe_radius, no_guessing);
+            assert!(game.open(3, 3).is_ok());
+            assert_eq!(game.mines.len(), 10);
+            assert!(off_edges(&game));
+        }
+        let mut game = new_game(None, false);
+        game.resize(9, 9);
+        assert!(off_edges(&game));
+    }
+}
+
+#[test]
+fn tutorial_steps_are_right() {
+    let mines = HashSet::from(TUTORIAL_MINES);
+    let mut game = Minesweeper::new_with_mines(settings(5, 5, MinesweeperVariant::Normal), mines);
+    // each step opens a safe square (still closed), or flags a mine
+    for (_, move_type, (x, y)) in TUTORIAL_STEPS {
+        assert!(!game.open_squares.contains(&(x, y)));
+        assert_eq!(game.mines.contains(&(x, y)), move_type == MoveType::Flag);
+        match move_type {
+            MoveType::Open => {
+                game.open_move(x, y);
+            }
+            _ => game.flag_move(x, y),
+        }
+    }
+    // the squares left are safe, as the last message says
+    assert!(game.mines.is_subset(&game.flagged_squares));
+    for (x, y) in game.closed_cells().collect::<Vec<Position>>() {
+        game.open_move(x, y);
+    }
+    assert!(game.state == GameState::Won);
+}
+
+#[test]
+fn max_cascade() {
+    let mut game = Minesweeper::new_with_mines(
+        GameSettings {
+            max_cascade: Some(2),
+            ..settings(6, 1, MinesweeperVariant::Normal)
+        },
+        HashSet::new(),
+    );
+    // the cascade stops after opening 2 squares beyond the one opened
+    game.open_move(0, 0);
+    assert_eq!(game.open_squares.len(), 3);
+    game.open_move(5, 0);
+    assert!(game.state == GameState::Won);
+    assert_eq!((game.direct_opens, game.cascade_opens), (2, 4));
+}
+
+#[test]
+fn multi_board() {
+    let settings = GameSettings {
+        num_mines: 5,
+        seed: Some(1),
+        ..settings(4, 4, MinesweeperVariant::Normal)
+    };
+    let mut multi = multi::MultiBoard::new(settings, 2);
+    // the mines are split between the boards, with the leftover one going to the first
+    let counts: Vec<usize> = multi
+        .boards
+        .iter()
+        .map(|board| board.mine_count())
+        .collect();
+    assert_eq!(counts, [3, 2]);
+    // winning one board isn't enough, but losing one is
+    multi.boards[0].state = GameState::Won;
+    assert!(multi.state() == GameState::Playing);
+    multi.boards[1].state = GameState::Lost;
+    assert!(multi.state() == GameState::Lost);
+    // and every board must be won to win
+    multi.boards[1].state = GameState::Won;
+    assert!(multi.state() == GameState::Won);
+}
+
+#[test]
+fn open_flagged() {
+    let mines = HashSet::from([(2, 0)]);
+    let mut game = Minesweeper::new_with_mines(settings(3, 1, MinesweeperVariant::Normal), mines);
+    // by default, the flag protects the square
+    assert!(game.flag(0, 0).is_ok());
+    assert_eq!(game.open(0, 0), Ok(0));
+    assert!(game.flagged_squares.contains(&(0, 0)));
+    // it can be removed by opening instead
+    game.open_flagged = OpenFlagged::Unflag;
+    assert_eq!(game.open(0, 0), Ok(2)); // cascades to (1, 0)
+    assert!(game.flagged_squares.is_empty());
+    assert!("warn".parse() == Ok(OpenFlagged::Warn));
+}
+
+#[test]
+fn three_bv() {
+    let three_bv = |mines: &[Position]| {
+        let mines = HashSet::from_iter(mines.iter().copied());
+        Minesweeper::new_with_mines(settings(5, 1, MinesweeperVariant::Normal), mines).three_bv()
+    };
+    // a cascade each side of the mine
+    assert_eq!(three_bv(&[(2, 0)]), 2);
+    // no cascades, so each safe square is a click
+    assert_eq!(three_bv(&[(1, 0), (3, 0)]), 3);
+}
+
+#[test]
+fn leaderboard_top_scores() {
+    let score = |points, board: &str, name: &str| leaderboard::Score {
+        points,
+        board: board.to_owned(),
+        name: name.to_owned(),
+    };
+    let scores = [
+        score(100, "9x9 normal 10", "a"),
+        score(300, "16x16 normal 40", "b"),
+        score(200, "9x9 normal 10", "c d"),
+        score(200, "9x9 normal 10", "e"),
+    ];
+    // only scores on the same board, highest first (and first recorded first on ties)
+    let top = leaderboard::top(&scores, "9x9 normal 10");
+    let names: Vec<&str> = top.iter().map(|score| score.name.as_str()).collect();
+    assert_eq!(names, ["c d", "e", "a"]);
+    // scores round trip through the leaderboard file's lines, names with spaces and all
+    assert_eq!(scores[2].to_string().parse(), Ok(scores[2].clone()));
+    assert!("abc\t9x9 normal 10\ta"
+        .parse::<leaderboard::Score>()
+        .is_err());
+    // names which would split a score's line are rejected
+    assert_eq!(parse_player_name("c d".to_owned()), Ok("c d".to_owned()));
+    for name in ["c\td", "c\nd", "c\r"] {
+        assert!(parse_player_name(name.to_owned()).is_err());
+    }
+}
+
+#[test]
+fn assisted_games_stay_off_the_leaderboard() {
+    let game = |settings: GameSettings| {
+        let mut game = Minesweeper::new_with_mines(settings, HashSet::from([(0, 0)]));
+        game.state = GameState::Won;
+        game
+    };
+    let board = || settings(3, 3, MinesweeperVariant::Normal);
+    assert!(game(board()).assistance().is_empty());
+    let assisted = game(GameSettings {
+        lives: Some(2),
+        pre_open: Some(PreOpen::Zero),
+        no_guessing: true,
+        safe_radius: Some(1),
+        ..board()
+    });
+    assert_eq!(
+        assisted.assistance(),
+        [
+            "lives",
+            "pre-opened squares",
+            "no guessing",
+            "a safe radius"
+        ]
+    );
+    let path = std::env::temp_dir().join("minesweeper-assisted-leaderboard");
+    let path = path.to_str().unwrap();
+    let _ = std::fs::remove_file(path);
+    assisted.record_score(path);
+    assert_eq!(leaderboard::load(path).unwrap().len(), 0);
+    game(board()).record_score(path);
+    assert_eq!(leaderboard::load(path).unwrap().len(), 1);
+    let _ = std::fs::remove_file(path);
+}
+
+#[test]
+fn pre_open_corner() {
+    // the top left corner is a mine, and of the other corners, the top right and bottom left
+    // have no mines near (the top right coming first in reading order)
+    let mines = HashSet::from([(0, 0), (2, 1), (3, 3)]);
+    let game = Minesweeper::new_with_mines(settings(5, 5, MinesweeperVariant::Normal), mines);
+    assert_eq!(game.safest_corner(), Some((4, 0)));
+    // with almost every square a mine, a corner is still opened (moving its mine elsewhere
+    // if it was one)
+    let game = Minesweeper::new(GameSettings {
+        num_mines: 14,
+        seed: Some(3),
+        pre_open: Some(PreOpen::Corner),
+        ..settings(4, 4, MinesweeperVariant::Normal)
+    });
+    assert_eq!(game.open_squares.len(), 1);
+    assert_eq!(game.mine_count(), 14);
+    assert!(game.state == GameState::Playing);
+}
+
+#[test]
+fn apply_actions() {
+    let mines = HashSet::from([(2, 0)]);
+    let mut game = Minesweeper::new_with_mines(
+        GameSettings {
+            lives: Some(2),
+            ..settings(4, 1, MinesweeperVariant::Normal)
+        },
+        mines,
+    );
+    assert_eq!(game.apply(Action::Flag((3, 0))), ActionResult::Flagged);
+    assert_eq!(game.apply(Action::Flag((3, 0))), ActionResult::Unflagged);
+    assert_eq!(game.apply(Action::Open((0, 0))), ActionResult::Opened(2));
+    assert_eq!(game.apply(Action::Open((0, 0))), ActionResult::Nothing);
+    assert_eq!(game.apply(Action::Open((2, 0))), ActionResult::MineHit);
+    assert!(game.state() == GameState::Playing);
+    assert_eq!(game.apply(Action::Surrender((3, 0))), ActionResult::Ended);
+    assert_eq!(game.apply(Action::Open((3, 0))), ActionResult::Nothing);
+    // every action but the last did something, so is recorded for replays
+    assert_eq!(game.moves.len(), 5);
+    // actions round trip through strings
+    for action in [Action::Open((3, 4)), Action::Flag((0, 1)), Action::Quit] {
+        assert_eq!(action.to_string().parse(), Ok(action));
+    }
+    assert!("open 3".parse::<Action>().is_err());
+}
+
+#[test]
+fn only_guessing_left() {
+    let mut game = Minesweeper::new_with_mines(
+        settings(2, 2, MinesweeperVariant::Normal),
+        HashSet::from([(0, 1)]),
+    );
+    // the first open is always a guess, so isn't pointed out
+    assert!(!game.only_guessing_left());
+    // the 1 could be from any of the three squares left
+    assert_eq!(game.open(1, 0), Ok(1));
+    assert!(game.only_guessing_left());
+    assert!(!subset_board().only_guessing_left());
+}
+
+#[test]
+fn state_hash() {
+    let board = |mines: &[Position]| {
+        let mines = HashSet::from_iter(mines.iter().copied());
+        Minesweeper::new_with_mines(settings(4, 4, MinesweeperVariant::Normal), mines)
+    };
+    // the same board hashes the same, however its sets were built
+    let mut game = board(&[(0, 0), (3, 2), (1, 3)]);
+    assert_eq!(
+        game.state_hash(),
+        board(&[(1, 3), (0, 0), (3, 2)]).state_hash()
+    );
+    assert_eq!(game.state_hash(), game.clone().state_hash());
+    // but different mines, squares open or flagged, or variants hash differently
+    let hash = game.state_hash();
+    assert_ne!(hash, board(&[(0, 0), (3, 2), (1, 2)]).state_hash());
+    let mut flagged = game.clone();
+    assert_eq!(flagged.flag(0, 0), Ok(()));
+    assert_ne!(hash, flagged.state_hash());
+    assert!(game.open(3, 0).is_ok());
+    assert_ne!(hash, game.state_hash());
+    let mut knight = board(&[(0, 0), (3, 2), (1, 3)]);
+    knight.variant = MinesweeperVariant::KnightPaths;
+    assert_ne!(hash, knight.state_hash());
+}
+
+#[test]
+fn large_frontiers_are_bounded_by_their_numbers() {
+    // mines under every other square of the top row, with the bottom row open
+    let mines: HashSet<Position> = (0..40).step_by(2).map(|x| (x, 0)).collect();
+    let mut game = Minesweeper::new_with_mines(settings(40, 2, MinesweeperVariant::Normal), mines);
+    game.open_squares.extend((0..40).map(|x| (x, 1)));
+    // the 40 squares are too many to search, but the 1s under the mines each only allow one
+    // mine among the squares they count
+    assert_eq!(game.max_frontier_mines(), 20);
+    // a smaller frontier is searched
+    let mines: HashSet<Position> = (0..10).step_by(2).map(|x| (x, 0)).collect();
+    let mut game = Minesweeper::new_with_mines(settings(10, 2, MinesweeperVariant::Normal), mines);
+    game.open_squares.extend((0..10).map(|x| (x, 1)));
+    assert_eq!(game.max_frontier_mines(), 5);
+}
+
+#[test]
+fn large_frontiers_are_estimated() {
+    // the same 40 square frontier is too many to search every arrangement of, so its 20 mines
+    // are spread evenly over it
+    let mines: HashSet<Position> = (0..40).step_by(2).map(|x| (x, 0)).collect();
+    let mut game = Minesweeper::new_with_mines(settings(40, 2, MinesweeperVariant::Normal), mines);
+    game.open_squares.extend((0..40).map(|x| (x, 1)));
+    let probabilities = game.mine_probabilities();
+    assert_eq!(probabilities.len(), 40);
+    assert!(probabilities
+        .values()
+        .all(|&chance| (chance - 0.5).abs() < 1e-9));
+}
+
+#[test]
+fn guesses_on_large_frontiers() {
+    // guessing uses the estimated chances, so doesn't search every arrangement either
+    let mines: HashSet<Position> = (0..40).step_by(2).map(|x| (x, 0)).collect();
+    let mut game = Minesweeper::new_with_mines(settings(40, 2, MinesweeperVariant::Normal), mines);
+    game.open_squares.extend((0..40).map(|x| (x, 1)));
+    let guess = game.best_guess(GuessStrategy::Position);
+    assert!(guess.is_some_and(|(pos, chance)| pos == (0, 0) && (chance - 0.5).abs() < 1e-9));
+}
+
+#[test]
+fn over_flagged() {
+    let mut game = Minesweeper::new_with_mines(
+        settings(3, 2, MinesweeperVariant::Normal),
+        HashSet::from([(1, 1)]),
+    );
+    // the 1s in the top row allow only one mine below them
+    for x in 0..3 {
+        assert!(game.open(x, 0).is_ok());
+    }
+    assert_eq!(game.max_frontier_mines(), 1);
+    assert_eq!(game.flag(1, 1), Ok(()));
+    assert_eq!(game.over_flagged(), None);
+    assert_eq!(game.flag(0, 1), Ok(()));
+    assert_eq!(game.over_flagged(), Some((2, 1)));
+}
+
+#[test]
+fn custom_win_condition() {
+    // win by opening the far corner, however much else is left to open
+    let target: WinCondition = Arc::new(|game| game.open_squares.contains(&(4, 4)));
+    let mut game = Minesweeper::new_with_mines(
+        GameSettings {
+            win_condition: Some(target),
+            ..settings(5, 5, MinesweeperVariant::Normal)
+        },
+        HashSet::from([(0, 0), (3, 3)]),
+    );
+    assert_eq!(game.open(1, 1), Ok(1));
+    assert!(game.state() == GameState::Playing);
+    assert_eq!(game.open(4, 4), Ok(1));
+    assert!(game.state() == GameState::Won);
+    // after winning, safe squares left closed open as usual, and mines are revealed
+    assert_eq!(game.open(1, 0), Ok(1));
+    assert!(game.square_view(1, 0) == SquareView::Open(1));
+    assert_eq!(game.open(3, 3), Ok(0));
+    assert!(game.state() == GameState::Won);
+    assert!(game.square_view(3, 3) == SquareView::Mine);
+}
+
+#[test]
+fn difficulty_map_with_custom_win_condition() {
+    // . . . A .
+    // . . . B C
+    // . . . T .
+    // opening the top left corner shows B is a mine, so T is safe and opening it wins,
+    // leaving the safe squares right of A and below C closed
+    let target: WinCondition = Arc::new(|game| game.open_squares.contains(&(3, 2)));
+    let mut game = Minesweeper::new_with_mines(
+        GameSettings {
+            win_condition: Some(target),
+            ..settings(5, 3, MinesweeperVariant::Normal)
+        },
+        HashSet::from([(3, 0), (3, 1), (4, 1)]),
+    );
+    assert!(game.open(0, 0).is_ok());
+    let rounds = game.difficulty_map();
+    assert_eq!(rounds.get(&(3, 2)), Some(&1));
+    // the mines left are known once the board is won, but the safe squares left aren't
+    assert!(game.mines.iter().all(|mine| rounds.contains_key(mine)));
+    assert!(!rounds.contains_key(&(4, 0)) && !rounds.contains_key(&(4, 2)));
+}
+
+#[test]
+fn difficulty_map() {
+    // 4x4 board with mines at A and B, opened from the top right corner:
+    // A . . .
+    // . . . .
+    // . B . .
+    // . . . .
+    let mines = HashSet::from([(0, 0), (1, 2)]);
+    let mut game = Minesweeper::new_with_mines(settings(4, 4, MinesweeperVariant::Normal), mines);
+    assert!(game.open(3, 0).is_ok());
+    // the numbers shown prove B and the squares left of and below it straight away, and the rest
+    // once the newly opened squares show their numbers
+    let rounds = game.difficulty_map();
+    let round = |x, y| rounds.get(&(x, y)).copied();
+    assert_eq!(rounds.len(), 16 - game.open_squares.len());
+    assert_eq!([round(1, 2), round(0, 2), round(1, 3)], [Some(1); 3]);
+    assert_eq!([round(0, 0), round(0, 1), round(0, 3)], [Some(2); 3]);
+    // the solver marks every closed square, so none are displayed as closed
+    assert!(!game.format_difficulty_map().contains(Theme::CLASSIC.closed));
+}
+
+#[test]
+fn fog_hides_far_numbers() {
+    let mut game = Minesweeper::new_with_mines(
+        GameSettings {
+            fog: Some(2),
+            ..settings(7, 2, MinesweeperVariant::Normal)
+        },
+        HashSet::from([(6, 0)]),
+    );
+    // the cascade opens every square but the last col, but only shows the numbers of those
+    // within 2 squares of the one opened
+    assert_eq!(game.open(0, 0), Ok(12));
+    assert!(game.square_view(2, 1) == SquareView::Open(0));
+    assert!(game.square_view(5, 0) == SquareView::Fog);
+    // opening a fogged square directly shows its number
+    assert_eq!(game.open(5, 0), Ok(1));
+    assert!(game.square_view(5, 0) == SquareView::Open(1));
+    assert_eq!(game.open(5, 0), Ok(0));
+    // fog lifts once the game is over
+    assert_eq!(game.open(6, 1), Ok(1));
+    assert!(game.state() == GameState::Won);
+    assert!(game.square_view(4, 1) == SquareView::Open(0));
+}
+
+#[test]
+fn opening_fogged_squares_is_an_action() {
+    let mut game = Minesweeper::new_with_mines(
+        GameSettings {
+            fog: Some(2),
+            ..settings(7, 2, MinesweeperVariant::Normal)
+        },
+        HashSet::from([(6, 0)]),
+    );
+    assert_eq!(game.apply(Action::Open((0, 0))), ActionResult::Opened(12));
+    // the fogged square was already open, but showing its number is still a move
+    assert_eq!(game.apply(Action::Open((5, 0))), ActionResult::Opened(1));
+    assert_eq!(game.apply(Action::Open((5, 0))), ActionResult::Nothing);
+    assert_eq!(game.moves.len(), 2);
+}
+
+#[test]
+fn satisfied_numbers() {
+    let mut game = subset_board();
+    assert!(game.satisfied_numbers().is_empty());
+    // flagging the mine satisfies every number next to it
+    assert_eq!(game.flag(1, 1), Ok(()));
+    assert_eq!(game.satisfied_numbers(), [(0, 0), (1, 0), (2, 0)]);
+    // a wrong flag satisfies the numbers it is next to too, as the flags are taken at their word
+    assert_eq!(game.flag(1, 1), Ok(()));
+    assert_eq!(game.flag(2, 1), Ok(()));
+    assert_eq!(game.satisfied_numbers(), [(1, 0), (2, 0)]);
+    // satisfied numbers are dimmed, if colors can be used
+    assert_eq!(Theme::CLASSIC.dimmed(1), "\x1b[2m1\x1b[0m ");
+    assert_eq!(Theme::CLASSIC.without_colors().dimmed(1), "1 ");
+}
+
+#[test]
+fn simulations_are_reproducible() {
+    // a safe radius moves mines away from the first open, so every game has to start from
+    // the same opening for them all to be played on the same board
+    let game = Minesweeper::new(GameSettings {
+        num_mines: 10,
+        safe_radius: Some(1),
+        ..settings(7, 7, MinesweeperVariant::Normal)
+    });
+    let stats = game.simulate(50, Some((3, 3)), 1);
+    let again = game.simulate(50, Some((3, 3)), 1);
+    assert_eq!(
+        (stats.wins, stats.clicks_to_loss),
+        (again.wins, again.clicks_to_loss)
+    );
+    // the opening is kept clear of mines, so it is never what loses a game
+    let start = game.simulate(50, Some((3, 3)), 2);
+    assert!(start.clicks_to_loss.iter().all(|&clicks| clicks > 0));
+    assert_eq!(start.seed, 2);
+}
+
+#[test]
+fn solver_trace() {
+    // each decision is traced, from the board the solver starts with to how it ends
+    let game = GameSettings {
+        trace_solver: true,
+        ..settings(3, 2, MinesweeperVariant::Normal)
+    };
+    let mut game = Minesweeper::new_with_mines(game, HashSet::from([(1, 1)]));
+    for x in 0..3 {
+        assert_eq!(game.open(x, 0), Ok(1));
+    }
+    let (steps, state, trace) = game.solve_traced(game.trace_solver);
+    assert!(state == GameState::Won);
+    assert_eq!(
+        trace,
+        [
+            "start open=3 closed=3",
+            "step=1 technique=subset sources=2,1;1,1 safe=3,2 mines= confidence=certain",
+            "step=2 technique=single sources=3,1 safe= mines=2,2 confidence=certain",
+            "step=3 technique=single sources=1,1 safe=1,2 mines= confidence=certain",
+            "solved steps=3",
+        ]
+    );
+    // the trace is of the same steps as solving without one, which traces nothing
+    let (untraced_steps, _, untraced) = game.solve_traced(false);
+    assert_eq!(steps.len(), untraced_steps.len());
+    assert!(untraced.is_empty());
+    // a board the solver gets stuck on says so
+    let stuck = board(MinesweeperVariant::Normal);
+    assert_eq!(
+        stuck.solve_traced(true).2.last().map(String::as_str),
+        Some("stuck steps=0 closed=49 known_mines=0")
+    );
+}
+
+#[test]
+fn spectated_games_play_to_the_end() {
+    // with the solver guessing whenever it is stuck (including for the first open),
+    // a spectated game is played to the end without asking the player for anything
+    for seed in 0..5 {
+        let mut game = Minesweeper::new(GameSettings {
+            num_mines: 10,
+            seed: Some(seed),
+            spectate: true,
+            auto_guess: Some(GuessStrategy::Corner),
+            ..settings(9, 9, MinesweeperVariant::Normal)
+        });
+        game.play();
+        assert!(matches!(game.state(), GameState::Won | GameState::Lost));
+    }
+    // without guessing, the solver takes over once the player has opened enough to go on
+    let mut game = Minesweeper::new_with_mines(
+        GameSettings {
+            spectate: true,
+            ..settings(3, 2, MinesweeperVariant::Normal)
+        },
+        HashSet::from([(1, 1)]),
+    );
+    for x in 0..3 {
+        assert_eq!(game.apply(Action::Open((x, 0))), ActionResult::Opened(1));
+    }
+    game.solver_moves();
+    assert!(game.state() == GameState::Won);
+    assert_eq!(game.flagged_squares, HashSet::from([(1, 1)]));
+}
+
+#[test]
+fn prompts_replace_messages_or_are_left_out() {
+    let prompt = |custom: Option<&str>, quiet: bool| {
+        let mut out = Vec::new();
+        write_prompt(&mut out, "Enter move x: ", custom, quiet).unwrap();
+        String::from_utf8(out).unwrap()
+    };
+    assert_eq!(prompt(None, false), "Enter move x: \n");
+    assert_eq!(prompt(None, true), "");
+    // custom prompts are displayed on the input's line, even when quiet
+    assert_eq!(prompt(Some("x> "), false), "x> ");
+    assert_eq!(prompt(Some("x> "), true), "x> ");
+}
+
+#[test]
+fn move_prompts_are_kept_apart() {
+    let game = Minesweeper::new(GameSettings {
+        quiet: true,
+        prompts: Prompts {
+            x: Some("x> ".to_owned()),
+            y: Some("y> ".to_owned()),
+            move_type: None,
+        },
+        ..Default::default()
+    });
+    assert!(game.quiet);
+    assert_eq!(game.prompts.x.as_deref(), Some("x> "));
+    assert_eq!(game.prompts.y.as_deref(), Some("y> "));
+    assert_eq!(game.prompts.move_type, None);
+    // boards of a multi-board game share the settings' prompts
+    let multi = multi::MultiBoard::new(
+        GameSettings {
+            prompts: game.prompts.clone(),
+            ..Default::default()
+        },
+        2,
+    );
+    assert!(multi
+        .boards
+        .iter()
+        .all(|board| board.prompts.y.as_deref() == Some("y> ")));
+}
+
+#[test]
+fn the_core_keeps_notices_for_the_display() {
+    let mut game = Minesweeper::new(GameSettings {
+        no_guessing: true,
+        seed: Some(1),
+        ..GameSettings::new(9, 9, 10, MinesweeperVariant::Normal)
+    });
+    assert!(game.notices.is_empty());
+    assert_eq!(
+        game.apply(Action::Open((4, 4))),
+        ActionResult::Opened(game.open_squares.len())
+    );
+    assert_eq!(game.notices.len(), 1);
+    assert!(game.notices[0].starts_with("Generated board solvable without guessing from seed "));
+    // they are only displayed once
+    game.print_notices();
+    assert!(game.notices.is_empty());
+    // mines which can't be spaced apart are placed anyway, with a notice saying so
+    let game = Minesweeper::new(GameSettings {
+        min_mine_spacing: Some(3),
+        seed: Some(1),
+        ..GameSettings::new(4, 4, 5, MinesweeperVariant::Normal)
+    });
+    assert_eq!(game.notices, ["Warning: unable to space 5 mines 3 apart."]);
+}
@@ -0,0 +1,231 @@
+// themes for displaying the board: the glyphs used for each kind of square,
+// the colors of numbers, and what mines are called in messages.
+// palettes of number colors can replace a theme's own colors
+
+use std::fmt;
+use std::str::FromStr;
+
+// number of terminal columns every square of the board takes up
+const CELL_WIDTH: usize = 2;
+
+// struct to store a theme
+// each glyph takes up 2 chars (or one wide char, like an emoji), so the board stays lined up
+#[derive(Clone, Copy)]
+pub struct Theme {
+    pub name: &'static str,      // name of theme (as used on the command line)
+    pub closed: &'static str,    // glyph for a closed square
+    pub open: &'static str,      // glyph for an open square with no mines near
+    pub flag: &'static str,      // glyph for a flagged square
+    pub mine: &'static str,      // glyph for a revealed mine
+    colors: [&'static str; 8],   // ANSI color codes for numbers 1-8 (empty for no color)
+    pub mine_word: &'static str, // what mines are called in messages, eg. "mine"
+    colored: bool,               // whether color codes can be used at all (eg. to dim numbers)
+}
+
+impl Theme {
+    // the classic theme, as the game has always looked
+    pub const CLASSIC: Self = Self {
+        name: "classic",
+        closed: ". ",
+        open: "  ",
+        flag: "F ",
+        mine: "# ",
+        colors: [""; 8],
+        mine_word: "mine",
+        colored: true,
+    };
+
+    // colors of numbers in the original windows game: blue, green, red, navy, maroon, teal,
+    // black (shown as magenta, to be visible on dark terminals) and gray
+    const WINDOWS_COLORS: [&'static str; 8] = ["94", "32", "91", "34", "31", "36", "35", "90"];
+
+    // colors of numbers which stay distinguishable with red-green (and most other) color
+    // blindness, from the okabe-ito palette (as the nearest 256-color terminal colors):
+    // sky blue, orange, reddish purple, blue, vermillion, bluish green, yellow and gray.
+    // 1 and 2, the most common numbers, are the blue and orange, which are the easiest pair
+    // to tell apart, and 3 avoids red so it can't be confused with 2
+    const COLORBLIND_COLORS: [&'static str; 8] = [
+        "38;5;74", "38;5;214", "38;5;175", "38;5;26", "38;5;166", "38;5;36", "38;5;227", "38;5;245",
+    ];
+
+    // all built-in themes
+    pub const ALL: [Self; 3] = [
+        Self::CLASSIC,
+        Self {
+            name: "emoji",
+            closed: "⬜",
+            open: "  ",
+            flag: "🚩",
+            mine: "💣",
+            colors: Self::WINDOWS_COLORS,
+            mine_word: "bomb",
+            colored: true,
+        },
+        Self {
+            name: "retro",
+            closed: "[]",
+            open: "  ",
+            flag: "|>",
+            mine: "**",
+            colors: Self::WINDOWS_COLORS,
+            mine_word: "bomb",
+            colored: true,
+        },
+    ];
+
+    // fn to get the names of all built-in themes, for err msgs
+    pub fn list() -> String {
+        Self::ALL.map(|theme| theme.name).join(", ")
+    }
+
+    // fn to get the same theme with its numbers colored from a palette instead
+    pub fn with_palette(self, palette: Palette) -> Self {
+        Self {
+            colors: palette.colors,
+            ..self
+        }
+    }
+
+    // fn to get the same theme without colors (for when not displaying to a terminal)
+    pub fn without_colors(self) -> Self {
+        Self {
+            colors: [""; 8],
+            colored: false,
+            ..self
+        }
+    }
+
+    // fn to format a number which needs no more attention, dimmed
+    // (formatted like any other number if colors can't be used)
+    pub fn dimmed(&self, number: usize) -> String {
+        if self.colored {
+            format!("\x1b[2m{number}\x1b[0m ")
+        } else {
+            self.number(number)
+        }
+    }
+
+    // fn to format a flagged mine showing how many mines are near it (in detective mode), in the
+    // same number of columns as any other square: the flag's glyph then the number if they fit,
+    // otherwise (eg. for numbers from 10, or a wide flag) the number alone, reversed if colors
+    // can be used so that it still stands out as a flag
+    pub fn flag_number(&self, number: usize) -> String {
+        let flagged = format!("{}{number}", self.flag.trim_end());
+        let width = self.display_width(&flagged);
+        if width <= CELL_WIDTH {
+            return flagged + &" ".repeat(CELL_WIDTH - width);
+        }
+        let number = format!("{number:<CELL_WIDTH$}");
+        if self.colored {
+            format!("\x1b[7m{number}\x1b[0m")
+        } else {
+            number
+        }
+    }
+
+    // fn to get how many columns a line of the board takes up on a terminal: color codes take
+    // up none, and a glyph of the theme which is a single (wide) char takes up 2
+    pub fn display_width(&self, line: &str) -> usize {
+        let wide = [self.closed, self.open, self.flag, self.mine];
+        let mut width = 0;
+        let mut chars = line.chars();
+        while let Some(c) = chars.next() {
+            // skip color codes, which run from an escape to an m
+            if c == '\x1b' {
+                chars.by_ref().find(|&c| c == 'm');
+                continue;
+            }
+            width += if wide.iter().any(|glyph| glyph.chars().eq([c])) {
+                2
+            } else {
+                1
+            };
+        }
+        width
+    }
+
+    // fn to format a number of mines near a square, taking up 2 chars (for numbers below 10)
+    // numbers are colored if the theme has a color for them
+    pub fn number(&self, number: usize) -> String {
+        match number.checked_sub(1).and_then(|i| self.colors.get(i)) {
+            Some(color) if !color.is_empty() => format!("\x1b[{color}m{number}\x1b[0m "),
+            _ => format!("{number} "),
+        }
+    }
+}
+
+// err to raise if parse from str fails
+#[derive(Debug, PartialEq)]
+pub struct ThemeParseError;
+
+impl fmt::Display for ThemeParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Invalid theme")
+    }
+}
+
+// impl ability to parse from str
+impl FromStr for Theme {
+    // err to return if parsing fails
+    type Err = ThemeParseError;
+
+    // fn to parse theme from str, by name
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.to_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|theme| theme.name == name)
+            .ok_or(ThemeParseError)
+    }
+}
+
+// struct to store a palette of number colors
+#[derive(Clone, Copy)]
+pub struct Palette {
+    pub name: &'static str,    // name of palette (as used on the command line)
+    colors: [&'static str; 8], // ANSI color codes for numbers 1-8
+}
+
+impl Palette {
+    // all palettes
+    pub const ALL: [Self; 2] = [
+        Self {
+            name: "windows",
+            colors: Theme::WINDOWS_COLORS,
+        },
+        Self {
+            name: "colorblind",
+            colors: Theme::COLORBLIND_COLORS,
+        },
+    ];
+
+    // fn to get the names of all palettes, for err msgs
+    pub fn list() -> String {
+        Self::ALL.map(|palette| palette.name).join(", ")
+    }
+}
+
+// err to raise if parse from str fails
+#[derive(Debug, PartialEq)]
+pub struct PaletteParseError;
+
+impl fmt::Display for PaletteParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Invalid palette")
+    }
+}
+
+// impl ability to parse from str
+impl FromStr for Palette {
+    // err to return if parsing fails
+    type Err = PaletteParseError;
+
+    // fn to parse palette from str, by name
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.to_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|palette| palette.name == name)
+            .ok_or(PaletteParseError)
+    }
+}